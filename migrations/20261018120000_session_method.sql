@@ -0,0 +1,3 @@
+ALTER TABLE voting_session ADD COLUMN method TEXT NOT NULL DEFAULT 'cv';
+ALTER TABLE voting_session ADD COLUMN qv_tokens INTEGER;
+ALTER TABLE session_votes ADD COLUMN votes INTEGER NOT NULL DEFAULT 0;
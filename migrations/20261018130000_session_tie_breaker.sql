@@ -0,0 +1,2 @@
+ALTER TABLE voting_session ADD COLUMN tie_breaker TEXT NOT NULL DEFAULT 'ballot';
+ALTER TABLE voting_session ADD COLUMN tiebreak_seed INTEGER NOT NULL DEFAULT 0;
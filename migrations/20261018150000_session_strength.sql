@@ -0,0 +1 @@
+ALTER TABLE voting_session ADD COLUMN strength TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE session_participants ADD COLUMN weight_numer INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE session_participants ADD COLUMN weight_denom INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+ALTER TABLE voting_session ADD COLUMN qv_negative INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE voting_session ADD COLUMN qv_max_votes INTEGER;
+ALTER TABLE voting_session ADD COLUMN qv_banking INTEGER NOT NULL DEFAULT 0;
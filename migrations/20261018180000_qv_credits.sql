@@ -0,0 +1,8 @@
+CREATE TABLE qv_credits (
+discord_server TEXT NOT NULL,
+member TEXT NOT NULL,
+credits INTEGER NOT NULL,
+
+PRIMARY KEY(discord_server, member)
+);
+ALTER TABLE session_participants ADD COLUMN qv_credits INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,203 @@
+pub mod av {
+    use crate::voting_method::vm;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::error::Error;
+    use std::fmt;
+
+    pub type BallotChoice = u32;
+    /// The options a voter approves of.
+    pub type Ballot = BTreeSet<BallotChoice>;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Every option sharing the most approvals.
+        pub winners: Vec<BallotChoice>,
+        pub approvals: BTreeMap<BallotChoice, u64>,
+        pub num_ballots: u64,
+    }
+
+    impl std::fmt::Display for VoteBreakdown {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str(&format!(
+                "Winners - {:?}. Approvals: {:?} from {} ballots",
+                self.winners, self.approvals, self.num_ballots
+            ))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum VoteError {
+        NoBallots,
+        /// Ballot `ballot` (numbered from 0) approves an option that does not
+        /// exist.
+        UnknownOption {
+            ballot: usize,
+            choice: BallotChoice,
+        },
+    }
+
+    impl fmt::Display for VoteError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                VoteError::NoBallots => write!(f, "Must have at least one ballot to vote"),
+                VoteError::UnknownOption { ballot, choice } => {
+                    write!(f, "Ballot {ballot} approves unknown option {choice}")
+                }
+            }
+        }
+    }
+
+    impl Error for VoteError {}
+
+    #[test]
+    fn test_vote() {
+        struct Case {
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+            expected_winners: Vec<BallotChoice>,
+            expected_approvals: BTreeMap<BallotChoice, u64>,
+        }
+
+        let cases = [
+            Case {
+                num_choices: 3,
+                ballots: vec![
+                    Ballot::from([0, 1]),
+                    Ballot::from([1]),
+                    Ballot::from([1, 2]),
+                ],
+                expected_winners: vec![1],
+                expected_approvals: BTreeMap::from([(0, 1), (1, 3), (2, 1)]),
+            },
+            Case {
+                num_choices: 3,
+                ballots: vec![Ballot::from([0, 2]), Ballot::from([2, 0])],
+                expected_winners: vec![0, 2],
+                expected_approvals: BTreeMap::from([(0, 2), (1, 0), (2, 2)]),
+            },
+        ];
+
+        for case in cases.iter() {
+            let result = vote(case.num_choices, case.ballots.clone()).unwrap();
+            assert_eq!(result.winners, case.expected_winners);
+            assert_eq!(result.approvals, case.expected_approvals);
+        }
+
+        assert_eq!(vote(3, vec![]), Err(VoteError::NoBallots));
+        assert_eq!(
+            vote(3, vec![Ballot::from([3])]),
+            Err(VoteError::UnknownOption {
+                ballot: 0,
+                choice: 3
+            })
+        );
+    }
+
+    pub fn vote(num_choices: u32, ballots: Vec<Ballot>) -> Result<VoteBreakdown, VoteError> {
+        if ballots.is_empty() {
+            return Err(VoteError::NoBallots);
+        }
+
+        let mut approvals: BTreeMap<BallotChoice, u64> =
+            (0..num_choices).map(|choice| (choice, 0)).collect();
+        for (i, ballot) in ballots.iter().enumerate() {
+            for choice in ballot.iter() {
+                match approvals.get_mut(choice) {
+                    Some(count) => *count += 1,
+                    None => {
+                        return Err(VoteError::UnknownOption {
+                            ballot: i,
+                            choice: *choice,
+                        });
+                    }
+                }
+            }
+        }
+
+        let most = approvals.values().max().copied().unwrap_or(0);
+        let winners = approvals
+            .iter()
+            .filter(|(_, count)| **count == most)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        return Ok(VoteBreakdown {
+            winners,
+            approvals,
+            num_ballots: vm::count(ballots.len()),
+        });
+    }
+
+    /// Approval voting: the option approved by the most voters wins.
+    pub struct ApprovalVoting;
+
+    impl vm::VotingMethod for ApprovalVoting {
+        type Ballot = Ballot;
+        type Details = VoteBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            "Approval"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking: vm::ranking_by_score(breakdown.approvals.clone()),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Approval winner: {}.", winners[0]),
+                _ => format!("Tied approval winners: {}.", winners.join(", ")),
+            };
+
+            let approvals: Vec<String> = result
+                .approvals
+                .iter()
+                .map(|(choice, count)| {
+                    format!(
+                        "{} {count}/{}",
+                        vm::option_label(*choice, option_id_to_option),
+                        result.num_ballots
+                    )
+                })
+                .collect();
+            message += &format!("\nApprovals: {}", approvals.join(", "));
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![Ballot::from([0, 1]), Ballot::from([1])];
+        let outcome = ApprovalVoting.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![1]);
+        assert_eq!(outcome.ranking, vec![vec![1], vec![0], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            ApprovalVoting.describe(&outcome, &options),
+            "Approval winner: **Emma**.\nApprovals: 0 (Dune) 1/2, 1 (Emma) 2/2, 2 0/2"
+        );
+    }
+}
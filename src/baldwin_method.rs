@@ -0,0 +1,172 @@
+pub mod baldwin {
+    use crate::borda_count::borda;
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RoundBreakdown {
+        /// Borda points among the options still standing this round.
+        pub scores: BTreeMap<cv::BallotChoice, u64>,
+        /// Every option sharing the fewest points.
+        pub eliminated: Vec<cv::BallotChoice>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// The last option standing, or every option still tied at the end.
+        pub winners: Vec<cv::BallotChoice>,
+        pub rounds: Vec<RoundBreakdown>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_vote() {
+        // Knoxville has the fewest Borda points (107), then Memphis is last
+        // of the three left.
+        let ballots = cv::tennessee_ballots();
+
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.winners, vec![1]);
+        let eliminated: Vec<Vec<cv::BallotChoice>> = result
+            .rounds
+            .iter()
+            .map(|round| round.eliminated.clone())
+            .collect();
+        assert_eq!(eliminated, vec![vec![3], vec![0], vec![2]]);
+
+        // Truncated ballots where the Borda winner, 3, is knocked out in
+        // the final round once 0 and 1 are gone.
+        let ballots = cv::truncated_ballots();
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.winners, vec![2]);
+        assert_eq!(result.rounds[2].scores, BTreeMap::from([(2, 5), (3, 4)]));
+
+        // Everyone tied.
+        let ballots = vec![vec![vec![0], vec![1]], vec![vec![1], vec![0]]];
+        let result = vote(2, ballots).unwrap();
+        assert_eq!(result.winners, vec![0, 1]);
+        assert!(result.rounds.is_empty());
+
+        assert!(vote(3, vec![]).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+
+        let mut remaining: Vec<cv::BallotChoice> = (0..num_choices).collect();
+        let mut rounds: Vec<RoundBreakdown> = Vec::new();
+        while remaining.len() > 1 {
+            let scores = borda::scores(&final_outranking, &remaining);
+            let fewest = scores.values().min().copied().unwrap_or(0);
+            if scores.values().all(|points| *points == fewest) {
+                break;
+            }
+
+            let eliminated: Vec<cv::BallotChoice> = scores
+                .iter()
+                .filter(|(_, points)| **points == fewest)
+                .map(|(choice, _)| *choice)
+                .collect();
+            remaining.retain(|choice| !eliminated.contains(choice));
+            rounds.push(RoundBreakdown { scores, eliminated });
+        }
+
+        return Ok(VoteBreakdown {
+            winners: remaining,
+            rounds,
+            final_outranking,
+        });
+    }
+
+    /// Baldwin's method: repeatedly eliminate the Borda loser, recounting
+    /// points among the remaining options each round.
+    pub struct Baldwin;
+
+    impl vm::VotingMethod for Baldwin {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Baldwin"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+
+            // Later eliminations rank higher.
+            let mut ranking = vec![breakdown.winners.clone()];
+            for round in breakdown.rounds.iter().rev() {
+                ranking.push(round.eliminated.clone());
+            }
+
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking,
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Baldwin winner: {}.", winners[0]),
+                _ => format!("Tied Baldwin winners: {}.", winners.join(", ")),
+            };
+
+            for (i, round) in result.rounds.iter().enumerate() {
+                let eliminated: Vec<String> = round
+                    .eliminated
+                    .iter()
+                    .map(|choice| vm::option_label(*choice, option_id_to_option))
+                    .collect();
+                message += &format!(
+                    "\nRound {}: {}. Eliminated: {}",
+                    i + 1,
+                    borda::scores_str(&round.scores, option_id_to_option),
+                    eliminated.join(", ")
+                );
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            cv::Ballot::from([vec![0], vec![1], vec![2]]),
+            cv::Ballot::from([vec![1], vec![0], vec![2]]),
+            cv::Ballot::from([vec![0], vec![2], vec![1]]),
+        ];
+        let outcome = Baldwin.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            Baldwin.describe(&outcome, &options),
+            "Baldwin winner: **Dune**.
+Round 1: 0 (Dune) 5, 1 (Emma) 3, 2 1. Eliminated: 2
+Round 2: 0 (Dune) 2, 1 (Emma) 1. Eliminated: 1 (Emma)"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+pub mod borda {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Every option sharing the most points.
+        pub winners: Vec<cv::BallotChoice>,
+        pub scores: BTreeMap<cv::BallotChoice, u64>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_scores() {
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 5, 2, //
+                2, 0, 5, //
+                3, 2, 0, //
+            ],
+        );
+        assert_eq!(
+            scores(&m, &[0, 1, 2]),
+            BTreeMap::from([(0, 7), (1, 7), (2, 5)])
+        );
+        // Only the listed candidates count.
+        assert_eq!(scores(&m, &[0, 2]), BTreeMap::from([(0, 2), (2, 3)]));
+    }
+
+    /// Borda points of each of `candidates` counting only the others in
+    /// `candidates`: a point per ballot for every one of them it is ranked
+    /// above. Read straight off the summed outranking matrix, so options a
+    /// ballot ties or leaves unranked score nothing against each other.
+    pub fn scores(
+        final_outranking: &nalgebra::DMatrix<u32>,
+        candidates: &[cv::BallotChoice],
+    ) -> BTreeMap<cv::BallotChoice, u64> {
+        let index = |choice: &cv::BallotChoice| vm::index(*choice);
+        return candidates
+            .iter()
+            .map(|runner| {
+                let points = candidates
+                    .iter()
+                    .map(|opponent| u64::from(final_outranking[(index(runner), index(opponent))]))
+                    .sum();
+                (*runner, points)
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_vote() {
+        let ballots = cv::tennessee_ballots();
+
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.winners, vec![1]);
+        assert_eq!(
+            result.scores,
+            BTreeMap::from([(0, 126), (1, 194), (2, 173), (3, 107)])
+        );
+
+        let ballots = vec![cv::Ballot::from([vec![0, 1]]); 2];
+        assert_eq!(vote(2, ballots).unwrap().winners, vec![0, 1]);
+
+        assert!(vote(3, vec![]).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+        let candidates: Vec<cv::BallotChoice> = (0..num_choices).collect();
+        let scores = scores(&final_outranking, &candidates);
+
+        let best = scores.values().max().copied().unwrap_or(0);
+        let winners = scores
+            .iter()
+            .filter(|(_, points)| **points == best)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        return Ok(VoteBreakdown {
+            winners,
+            scores,
+            final_outranking,
+        });
+    }
+
+    /// Borda count on ranked ballots.
+    pub struct Borda;
+
+    impl vm::VotingMethod for Borda {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Borda"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking: vm::ranking_by_score(breakdown.scores.clone()),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Borda winner: {}.", winners[0]),
+                _ => format!("Tied Borda winners: {}.", winners.join(", ")),
+            };
+            message += &format!(
+                "\nPoints (one per ballot for each option ranked below): {}",
+                scores_str(&result.scores, option_id_to_option)
+            );
+            return message;
+        }
+    }
+
+    /// Formats Borda points as e.g. "0 (Dune) 12, 1 (Emma) 9".
+    pub fn scores_str(
+        scores: &BTreeMap<cv::BallotChoice, u64>,
+        option_id_to_option: &BTreeMap<vm::OptionId, String>,
+    ) -> String {
+        scores
+            .iter()
+            .map(|(choice, points)| {
+                format!(
+                    "{} {points}",
+                    vm::option_label(*choice, option_id_to_option)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            cv::Ballot::from([vec![0], vec![1], vec![2]]),
+            cv::Ballot::from([vec![1], vec![0]]),
+        ];
+        let outcome = Borda.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0, 1]);
+        assert_eq!(outcome.ranking, vec![vec![0, 1], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            Borda.describe(&outcome, &options),
+            "Tied Borda winners: **Dune**, **Emma**.
+Points (one per ballot for each option ranked below): 0 (Dune) 3, 1 (Emma) 3, 2 0"
+        );
+    }
+}
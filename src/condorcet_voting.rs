@@ -1,84 +1,312 @@
 pub mod cv {
+    use crate::voting_method::vm;
     use nalgebra;
+    use num_rational::Ratio;
+    use std::collections::BTreeMap;
     use std::convert::TryFrom;
     use std::error::Error;
     use std::fmt;
 
     pub type BallotChoice = u32;
-    pub type Ballot = Vec<BallotChoice>;
+    /// Ranked tiers from most to least preferred. Choices in the same tier are
+    /// ranked equally, e.g. `3 = 1 > 2` is `[[3, 1], [2]]`.
+    pub type Ballot = Vec<Vec<BallotChoice>>;
+    /// A voter's share of a vote, e.g. 1/2 for each member of a household
+    /// sharing one.
+    pub type Weight = Ratio<u32>;
+    pub type SchulzeResult = (Vec<(BallotChoice, u32)>, nalgebra::DMatrix<Link>);
 
-    #[derive(Debug, Clone)]
+    /// A ranked ballot counted `weight` times.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct WeightedBallot {
+        pub weight: Weight,
+        pub ballot: Ballot,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
     pub enum WinType {
         CondorcetWinner,
         SchulzeRanking,
     }
 
-    #[derive(Debug, Clone)]
+    /// How to pick a single winner (and a linear order) when the Schulze
+    /// ranking has ties.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum TieBreaker {
+        /// Report the tie and leave it unresolved.
+        None,
+        /// Schulze's tie-breaking ranking of candidates (TBRC): ballots are
+        /// drawn at random and each one orders the candidates the previous
+        /// ones left tied. Anything still tied is ordered randomly.
+        RandomBallot { seed: u64 },
+        /// A seeded random order of all candidates.
+        RandomOrder { seed: u64 },
+    }
+
+    impl fmt::Display for TieBreaker {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                TieBreaker::None => write!(f, "no tie-breaker"),
+                TieBreaker::RandomBallot { seed } => {
+                    write!(f, "Schulze's random-ballot tie-breaker (seed {seed})")
+                }
+                TieBreaker::RandomOrder { seed } => write!(f, "random order (seed {seed})"),
+            }
+        }
+    }
+
+    /// How the Schulze method measures the strength of a pairwise win of A
+    /// over B. They agree on complete ballots without ties but can differ
+    /// once ballots are truncated or rank options equally.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Strength {
+        /// The number of voters preferring A.
+        WinningVotes,
+        /// Voters preferring A minus voters preferring B.
+        Margins,
+        /// Voters preferring A divided by voters preferring B.
+        Ratio,
+        /// Winning votes, ties broken by fewer voters preferring B.
+        Combined,
+    }
+
+    impl fmt::Display for Strength {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Strength::WinningVotes => write!(f, "winning votes"),
+                Strength::Margins => write!(f, "margins"),
+                Strength::Ratio => write!(f, "ratio"),
+                Strength::Combined => write!(f, "winning votes, then losing votes"),
+            }
+        }
+    }
+
+    /// The strength of a link (or path) between two candidates under some
+    /// `Strength`. Any win is stronger than `Link::None`, and a matrix only
+    /// ever mixes `Link::None` with the variant of a single measure.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+    pub enum Link {
+        /// No win.
+        #[default]
+        None,
+        /// Winning votes or margin.
+        Votes(u32),
+        /// Winning over losing votes. An unopposed win beats any ratio;
+        /// unopposed wins compare by their votes.
+        Ratio { unopposed: bool, ratio: Ratio<u32> },
+        /// Winning votes, then fewer losing votes.
+        Combined {
+            votes_for: u32,
+            votes_against: std::cmp::Reverse<u32>,
+        },
+    }
+
+    impl Link {
+        /// The strength as text, with votes counted in units of 1/`vote_unit`
+        /// (see `weighted_outranking`).
+        pub fn in_votes(&self, vote_unit: u32) -> String {
+            let votes = |votes: u32| Ratio::new(votes, vote_unit);
+            match self {
+                Link::None => "0".to_string(),
+                Link::Votes(v) => votes(*v).to_string(),
+                Link::Ratio {
+                    unopposed: true,
+                    ratio,
+                } => format!("{}/0", votes(*ratio.numer())),
+                Link::Ratio {
+                    unopposed: false,
+                    ratio,
+                } => ratio.to_string(),
+                Link::Combined {
+                    votes_for,
+                    votes_against,
+                } => format!("{}–{}", votes(*votes_for), votes(votes_against.0)),
+            }
+        }
+    }
+
+    impl fmt::Display for Link {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.in_votes(1))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
     pub struct VoteBreakdown {
         pub winner: Option<BallotChoice>,
         pub win_type: Option<WinType>,
+        /// Every candidate tied for first before any tie-breaking.
+        pub tied_winners: Vec<BallotChoice>,
+        pub tie_breaker: TieBreaker,
+        /// The tie-breaking order of all candidates, if a tie had to be broken.
+        pub tie_break_order: Option<Vec<BallotChoice>>,
+        /// How the Schulze ranking measured pairwise wins.
+        pub strength: Strength,
         pub ballots: Vec<Ballot>,
-        pub ballot_outranking: Vec<nalgebra::DMatrix<u32>>,
+        /// How many times each of `ballots` counts.
+        pub weights: Vec<Weight>,
+        /// `final_outranking` counts votes in units of 1/`vote_unit`, so that
+        /// fractional weights sum to whole numbers. 1 without them.
+        pub vote_unit: u32,
         pub final_outranking: nalgebra::DMatrix<u32>,
-        pub schulze_result: Option<(Vec<(BallotChoice, u32)>, nalgebra::DMatrix<u32>)>,
+        /// The Schulze ranking of every candidate, computed even when there is
+        /// a Condorcet winner (who always tops it).
+        pub schulze_result: SchulzeResult,
+        /// Smallest set of candidates who beat everyone outside it.
+        pub smith_set: Vec<BallotChoice>,
+        /// Candidates not beaten by anyone they cannot beat back.
+        pub schwartz_set: Vec<BallotChoice>,
+        /// A cycle of head-to-head wins in the Smith set, if there is one.
+        pub cycle: Option<Vec<BallotChoice>>,
+    }
+
+    impl VoteBreakdown {
+        /// Every ballot's own outranking matrix, for debugging. The tally
+        /// itself never builds these.
+        pub fn ballot_outranking(&self) -> Vec<nalgebra::DMatrix<u32>> {
+            let num_choices = vm::option_id(self.final_outranking.nrows());
+            return self
+                .ballots
+                .iter()
+                .map(|ballot| ballot_to_outranking_matrix(ballot, num_choices))
+                .collect();
+        }
     }
 
     impl std::fmt::Display for VoteBreakdown {
         fn fmt(&self, fmt: &mut fmt::Formatter) -> std::fmt::Result {
-            match (self.winner, self.win_type.clone()) {
+            match (self.winner, &self.win_type) {
                 (Some(winner), Some(win_type)) => {
                     fmt.write_str(&format!("Winner - {winner}. Win Type: {:?}", win_type))?;
                 }
+                (Some(winner), None) => {
+                    fmt.write_str(&format!("Winner - {winner}."))?;
+                }
                 (None, _) => {
                     fmt.write_str("No winner!")?;
                 }
-                (_, _) => panic!("huh?!"), // FIXME
             };
-            fmt.write_str("\n")?;
-            fmt.write_str("Ballots:")?;
-            for ballot in &self.ballots {
-                fmt.write_str(&format!("  {:?}\n", ballot))?;
+            if self.tied_winners.len() > 1 {
+                fmt.write_str(&format!(
+                    " Tied winners: {:?}, broken with {}: {:?}",
+                    self.tied_winners, self.tie_breaker, self.tie_break_order
+                ))?;
             }
             fmt.write_str("\n")?;
-            fmt.write_str("Final outranking matrix")?;
-            fmt.write_str(&format!("  {}", self.final_outranking))?;
-
-            if let Some(schulze_result) = &self.schulze_result {
+            // Every ballot and its own outranking matrix only with `{:#}`:
+            // they are as long as the electorate.
+            if fmt.alternate() {
+                fmt.write_str("Ballots:")?;
+                for (ballot, weight) in self.ballots.iter().zip(self.weights.iter()) {
+                    fmt.write_str(&format!("  {:?} (weight {weight})\n", ballot))?;
+                }
                 fmt.write_str("\n")?;
-                fmt.write_str(&format!("  Schulze Ranking: {:?}", schulze_result.0))?;
-                fmt.write_str("  Schulze path strength matrix:")?;
-                fmt.write_str(&format!("    {}", schulze_result.1))?;
+                fmt.write_str("Ballot outranking matrices:")?;
+                for matrix in self.ballot_outranking() {
+                    fmt.write_str(&format!("  {}", matrix))?;
+                }
+            } else {
+                fmt.write_str(&format!("{} ballots\n", self.ballots.len()))?;
             }
+            fmt.write_str(&format!(
+                "Final outranking matrix (in 1/{} votes)",
+                self.vote_unit
+            ))?;
+            fmt.write_str(&format!("  {}", self.final_outranking))?;
+
+            fmt.write_str(&format!(
+                "\nSmith set: {:?}, Schwartz set: {:?}, cycle: {:?}\n",
+                self.smith_set, self.schwartz_set, self.cycle
+            ))?;
+
+            fmt.write_str("\n")?;
+            fmt.write_str(&format!("  Schulze Ranking: {:?}", self.schulze_result.0))?;
+            fmt.write_str(&format!(
+                "  Schulze path strength matrix ({}):",
+                self.strength
+            ))?;
+            fmt.write_str(&format!("    {}", self.schulze_result.1))?;
 
             Ok(())
         }
     }
 
-    // TODO: Better error...
-    #[derive(Debug)]
-    pub struct VoteError {
-        details: String,
-    }
-
-    impl VoteError {
-        fn new(msg: &str) -> VoteError {
-            VoteError {
-                details: msg.to_string(),
-            }
-        }
+    /// Why ranked ballots could not be counted. Ballots are numbered from 0
+    /// in the order they were passed in.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum VoteError {
+        NoBallots,
+        NoOptions,
+        /// A ballot ranks more options than there are.
+        BallotTooLong {
+            ballot: usize,
+            len: usize,
+            num_choices: u32,
+        },
+        UnknownOption {
+            ballot: usize,
+            choice: BallotChoice,
+        },
+        /// A ballot ranks the same option more than once.
+        DuplicateRank {
+            ballot: usize,
+            choice: BallotChoice,
+        },
+        /// `method` searches every ranking, so it is limited to `max` options.
+        TooManyOptions {
+            method: &'static str,
+            max: u32,
+            num_choices: u32,
+        },
+        WeightCount {
+            weights: usize,
+            ballots: usize,
+        },
+        /// The weights' denominators have no common multiple that fits in a u32.
+        WeightsTooFine,
+        /// A pairwise count does not fit in a u32.
+        TooManyBallots,
     }
 
     impl fmt::Display for VoteError {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "{}", self.details)
+            match self {
+                VoteError::NoBallots => write!(f, "Must have at least one ballot to vote"),
+                VoteError::NoOptions => write!(f, "Must have at least one option to vote"),
+                VoteError::BallotTooLong {
+                    ballot,
+                    len,
+                    num_choices,
+                } => write!(
+                    f,
+                    "Ballot {ballot} ranks {len} options but there are only {num_choices}"
+                ),
+                VoteError::UnknownOption { ballot, choice } => {
+                    write!(f, "Ballot {ballot} ranks unknown option {choice}")
+                }
+                VoteError::DuplicateRank { ballot, choice } => {
+                    write!(f, "Ballot {ballot} ranks option {choice} more than once")
+                }
+                VoteError::TooManyOptions {
+                    method,
+                    max,
+                    num_choices,
+                } => write!(
+                    f,
+                    "{method} supports at most {max} options, not {num_choices}"
+                ),
+                VoteError::WeightCount { weights, ballots } => {
+                    write!(f, "Got {weights} weights for {ballots} ballots")
+                }
+                VoteError::WeightsTooFine => {
+                    write!(f, "Ballot weights are too fine-grained to count")
+                }
+                VoteError::TooManyBallots => write!(f, "Too many ballots to count"),
+            }
         }
     }
 
-    impl Error for VoteError {
-        fn description(&self) -> &str {
-            &self.details
-        }
-    }
+    impl Error for VoteError {}
 
     #[test]
     fn howdoesindexingwork() {
@@ -102,7 +330,7 @@ pub mod cv {
         let cases = [
             Case {
                 // B, C, A, D
-                ballot: Vec::from([1, 2, 0, 3]),
+                ballot: Ballot::from([vec![1], vec![2], vec![0], vec![3]]),
                 expected: nalgebra::DMatrix::from_rows(&[
                     nalgebra::dvector![0, 0, 0, 1].transpose(), //
                     nalgebra::dvector![1, 0, 1, 1].transpose(), //
@@ -112,7 +340,7 @@ pub mod cv {
             },
             Case {
                 // C, B, A, D
-                ballot: Vec::from([2, 1, 0, 3]),
+                ballot: Ballot::from([vec![2], vec![1], vec![0], vec![3]]),
                 expected: nalgebra::DMatrix::from_rows(&[
                     nalgebra::dvector![0, 0, 0, 1].transpose(), //
                     nalgebra::dvector![1, 0, 0, 1].transpose(), //
@@ -120,11 +348,36 @@ pub mod cv {
                     nalgebra::dvector![0, 0, 0, 0].transpose(), //
                 ]),
             },
+            Case {
+                // C, A, then B and D unranked
+                ballot: Ballot::from([vec![2], vec![0]]),
+                expected: nalgebra::DMatrix::from_rows(&[
+                    nalgebra::dvector![0, 1, 0, 1].transpose(), //
+                    nalgebra::dvector![0, 0, 0, 0].transpose(), //
+                    nalgebra::dvector![1, 1, 0, 1].transpose(), //
+                    nalgebra::dvector![0, 0, 0, 0].transpose(), //
+                ]),
+            },
+            Case {
+                // B = C, A, then D unranked
+                ballot: Ballot::from([vec![1, 2], vec![0]]),
+                expected: nalgebra::DMatrix::from_rows(&[
+                    nalgebra::dvector![0, 0, 0, 1].transpose(), //
+                    nalgebra::dvector![1, 0, 0, 1].transpose(), //
+                    nalgebra::dvector![1, 0, 0, 1].transpose(), //
+                    nalgebra::dvector![0, 0, 0, 0].transpose(), //
+                ]),
+            },
+            Case {
+                // Nothing ranked
+                ballot: Ballot::new(),
+                expected: nalgebra::DMatrix::zeros(4, 4),
+            },
         ];
 
         for (i, case) in cases.iter().enumerate() {
             assert_eq!(
-                ballot_to_outranking_matrix(&case.ballot),
+                ballot_to_outranking_matrix(&case.ballot, 4),
                 case.expected,
                 "Case {}",
                 i,
@@ -133,19 +386,25 @@ pub mod cv {
     }
 
     //        https://en.wikipedia.org/wiki/Condorcet_method
-    fn ballot_to_outranking_matrix(ballot: &Ballot) -> nalgebra::DMatrix<u32> {
-        let mut candidate_to_ballot_position: Vec<usize> = vec![ballot.len() + 10; ballot.len()];
-        for (rank, candidate) in ballot.iter().enumerate() {
-            candidate_to_ballot_position[usize::try_from(*candidate).unwrap()] = rank;
-        }
-
-        for rank in candidate_to_ballot_position.iter() {
-            if *rank >= ballot.len().try_into().unwrap() {
-                panic!("Impossible rank {rank}")
+    // Candidates in the same tier are tied and neither gets a point over the
+    // other. Ballots may be truncated: every unranked candidate is treated as
+    // tied below all ranked candidates.
+    pub fn ballot_to_outranking_matrix(
+        ballot: &Ballot,
+        num_choices: u32,
+    ) -> nalgebra::DMatrix<u32> {
+        let num_choices = vm::index(num_choices);
+        let mut candidate_to_ballot_position: Vec<usize> = vec![usize::MAX; num_choices];
+        for (rank, tier) in ballot.iter().enumerate() {
+            for candidate in tier.iter() {
+                if let Some(position) = candidate_to_ballot_position.get_mut(vm::index(*candidate))
+                {
+                    *position = rank;
+                }
             }
         }
 
-        return nalgebra::DMatrix::from_fn(ballot.len(), ballot.len(), |runner, opponent| {
+        return nalgebra::DMatrix::from_fn(num_choices, num_choices, |runner, opponent| {
             if runner == opponent {
                 return 0;
             }
@@ -202,7 +461,7 @@ pub mod cv {
     }
 
     fn condorcet_winner(overall_matrix: &nalgebra::DMatrix<u32>) -> Option<BallotChoice> {
-        let num_candidates = overall_matrix.row(0).len();
+        let num_candidates = overall_matrix.nrows();
         for runner in 0..num_candidates {
             let mut runner_failed = false;
             for opponent in 0..num_candidates {
@@ -217,12 +476,163 @@ pub mod cv {
             }
 
             if !runner_failed {
-                return Some(runner.try_into().unwrap());
+                return Some(vm::option_id(runner));
             }
         }
         return None;
     }
 
+    #[test]
+    fn test_smith_and_schwartz_sets() {
+        struct Case {
+            m: nalgebra::DMatrix<u32>,
+            smith: Vec<BallotChoice>,
+            schwartz: Vec<BallotChoice>,
+            cycle: Option<Vec<BallotChoice>>,
+        }
+
+        let cases = [
+            // 0 beats 1 beats 2 beats 0, and all of them beat 3.
+            Case {
+                m: nalgebra::DMatrix::from_row_slice(
+                    4,
+                    4,
+                    &[
+                        0, 5, 3, 7, //
+                        2, 0, 5, 7, //
+                        4, 2, 0, 7, //
+                        0, 0, 0, 0, //
+                    ],
+                ),
+                smith: vec![0, 1, 2],
+                schwartz: vec![0, 1, 2],
+                cycle: Some(vec![0, 1, 2]),
+            },
+            // 0 ties 1, 1 beats 2, 2 beats 0. Nobody beats 1 outright.
+            Case {
+                m: nalgebra::DMatrix::from_row_slice(
+                    3,
+                    3,
+                    &[
+                        0, 1, 0, //
+                        1, 0, 2, //
+                        2, 0, 0, //
+                    ],
+                ),
+                smith: vec![0, 1, 2],
+                schwartz: vec![1],
+                cycle: None,
+            },
+            // A Condorcet winner is the whole Smith set.
+            Case {
+                m: nalgebra::DMatrix::from_row_slice(
+                    3,
+                    3,
+                    &[
+                        0, 2, 2, //
+                        1, 0, 2, //
+                        1, 1, 0, //
+                    ],
+                ),
+                smith: vec![0],
+                schwartz: vec![0],
+                cycle: None,
+            },
+        ];
+
+        for case in cases.iter() {
+            let smith = smith_set(&case.m);
+            assert_eq!(smith, case.smith);
+            assert_eq!(schwartz_set(&case.m), case.schwartz);
+            assert_eq!(find_cycle(&case.m, &smith), case.cycle);
+        }
+    }
+
+    // reaches[x][y] is true if there is a path from x to y where every step
+    // satisfies `step`.
+    fn reachability(num_candidates: usize, step: impl Fn(usize, usize) -> bool) -> Vec<Vec<bool>> {
+        let mut reaches: Vec<Vec<bool>> = (0..num_candidates)
+            .map(|x| (0..num_candidates).map(|y| x == y || step(x, y)).collect())
+            .collect();
+        for k in 0..num_candidates {
+            for x in 0..num_candidates {
+                for y in 0..num_candidates {
+                    if reaches[x][k] && reaches[k][y] {
+                        reaches[x][y] = true;
+                    }
+                }
+            }
+        }
+        return reaches;
+    }
+
+    /// The smallest set of candidates who each beat every candidate outside
+    /// the set head-to-head.
+    pub fn smith_set(overall_matrix: &nalgebra::DMatrix<u32>) -> Vec<BallotChoice> {
+        let num_candidates = overall_matrix.nrows();
+        // Members are the candidates that reach everyone by beating or tying.
+        let reaches = reachability(num_candidates, |x, y| {
+            overall_matrix[(x, y)] >= overall_matrix[(y, x)]
+        });
+        return (0..num_candidates)
+            .filter(|x| reaches[*x].iter().all(|r| *r))
+            .map(vm::option_id)
+            .collect();
+    }
+
+    /// The union of the smallest sets of candidates no outside candidate
+    /// beats head-to-head.
+    pub fn schwartz_set(overall_matrix: &nalgebra::DMatrix<u32>) -> Vec<BallotChoice> {
+        let num_candidates = overall_matrix.nrows();
+        let reaches = reachability(num_candidates, |x, y| {
+            overall_matrix[(x, y)] > overall_matrix[(y, x)]
+        });
+        // Members beat back, through some chain, everyone who beats them.
+        return (0..num_candidates)
+            .filter(|x| (0..num_candidates).all(|y| !reaches[y][*x] || reaches[*x][y]))
+            .map(vm::option_id)
+            .collect();
+    }
+
+    /// The shortest cycle of head-to-head wins among `candidates`, e.g.
+    /// `[0, 1, 2]` for 0 beats 1 beats 2 beats 0.
+    pub fn find_cycle(
+        overall_matrix: &nalgebra::DMatrix<u32>,
+        candidates: &[BallotChoice],
+    ) -> Option<Vec<BallotChoice>> {
+        let beats = |x: BallotChoice, y: BallotChoice| {
+            let (x, y) = (vm::index(x), vm::index(y));
+            overall_matrix[(x, y)] > overall_matrix[(y, x)]
+        };
+
+        let mut shortest: Option<Vec<BallotChoice>> = None;
+        for start in candidates.iter() {
+            // Breadth-first search for the shortest way back to `start`.
+            let mut previous: BTreeMap<BallotChoice, BallotChoice> = BTreeMap::new();
+            let mut queue = std::collections::VecDeque::from([*start]);
+            while let Some(current) = queue.pop_front() {
+                if beats(current, *start) {
+                    let mut cycle = vec![current];
+                    while let Some(before) = previous.get(cycle.last().unwrap()) {
+                        cycle.push(*before);
+                    }
+                    cycle.reverse();
+                    if shortest.as_ref().is_none_or(|s| cycle.len() < s.len()) {
+                        shortest = Some(cycle);
+                    }
+                    break;
+                }
+                for next in candidates.iter() {
+                    if *next != *start && !previous.contains_key(next) && beats(current, *next) {
+                        previous.insert(*next, current);
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+        return shortest;
+    }
+
     #[test]
     fn test_schulze_ranking() {
         struct Case {
@@ -259,13 +669,130 @@ pub mod cv {
         }];
 
         for case in cases.iter() {
-            let result = schulze_ranking(&case.m);
-            assert_eq!(result.1, case.expected_path_matrix);
+            let result = schulze_ranking(&case.m, Strength::WinningVotes);
+            assert_eq!(
+                result.1,
+                case.expected_path_matrix.map(|votes| match votes {
+                    0 => Link::None,
+                    votes => Link::Votes(votes),
+                })
+            );
             assert_eq!(result.0, case.expected);
         }
     }
 
-    fn schulze_path_matrix(overall_matrix: &nalgebra::DMatrix<u32>) -> nalgebra::DMatrix<u32> {
+    #[test]
+    fn test_link_matrix() {
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 4, 3, //
+                2, 0, 0, //
+                3, 2, 0, //
+            ],
+        );
+        let links = |strength| {
+            let links = link_matrix(&m, strength);
+            [links[(0, 1)], links[(1, 0)], links[(0, 2)], links[(2, 1)]]
+        };
+
+        assert_eq!(
+            links(Strength::WinningVotes),
+            [Link::Votes(4), Link::None, Link::None, Link::Votes(2)]
+        );
+        assert_eq!(
+            links(Strength::Margins),
+            [Link::Votes(2), Link::None, Link::None, Link::Votes(2)]
+        );
+        let ratio = |ratio: Ratio<u32>| Link::Ratio {
+            unopposed: false,
+            ratio,
+        };
+        assert_eq!(
+            links(Strength::Ratio),
+            [
+                ratio(Ratio::from(2)),
+                Link::None,
+                Link::None,
+                Link::Ratio {
+                    unopposed: true,
+                    ratio: Ratio::from(2)
+                }
+            ]
+        );
+        // Unopposed beats any ratio.
+        assert!(links(Strength::Ratio)[3] > links(Strength::Ratio)[0]);
+        let combined = links(Strength::Combined);
+        assert!(combined[0] > combined[3]);
+        assert!(
+            Link::Combined {
+                votes_for: 4,
+                votes_against: std::cmp::Reverse(1)
+            } > combined[0]
+        );
+    }
+
+    #[test]
+    fn test_strength_changes_winner() {
+        // Truncated ballots with a cycle: 1 beats 0 5-4, 0 beats 2 4-2 and
+        // 2 beats 1 5-3. The weakest link is 0 over 2 by winning votes but
+        // 1 over 0 by margin and by ratio.
+        let mut ballots: Vec<Ballot> = Vec::new();
+        ballots.extend(vec![Ballot::from([vec![0], vec![2]]); 3]);
+        ballots.extend(vec![Ballot::from([vec![1]]); 3]);
+        ballots.extend(vec![Ballot::from([vec![2], vec![1]]); 2]);
+        ballots.push(Ballot::from([vec![0]]));
+        let winner = |strength| {
+            vote(3, ballots.clone(), &TieBreaker::None, strength)
+                .unwrap()
+                .winner
+        };
+
+        assert_eq!(winner(Strength::WinningVotes), Some(2));
+        assert_eq!(winner(Strength::Combined), Some(2));
+        assert_eq!(winner(Strength::Margins), Some(0));
+        assert_eq!(winner(Strength::Ratio), Some(0));
+    }
+
+    /// The strength of every pairwise win in `overall_matrix` under
+    /// `strength`, `Link::None` where the runner does not win.
+    pub fn link_matrix(
+        overall_matrix: &nalgebra::DMatrix<u32>,
+        strength: Strength,
+    ) -> nalgebra::DMatrix<Link> {
+        return nalgebra::DMatrix::from_fn(
+            overall_matrix.nrows(),
+            overall_matrix.ncols(),
+            |runner, opponent| {
+                let votes_for = overall_matrix[(runner, opponent)];
+                let votes_against = overall_matrix[(opponent, runner)];
+                if votes_for <= votes_against {
+                    return Link::None;
+                }
+                return match strength {
+                    Strength::WinningVotes => Link::Votes(votes_for),
+                    Strength::Margins => Link::Votes(votes_for - votes_against),
+                    Strength::Ratio if votes_against == 0 => Link::Ratio {
+                        unopposed: true,
+                        ratio: Ratio::from(votes_for),
+                    },
+                    Strength::Ratio => Link::Ratio {
+                        unopposed: false,
+                        ratio: Ratio::new(votes_for, votes_against),
+                    },
+                    Strength::Combined => Link::Combined {
+                        votes_for,
+                        votes_against: std::cmp::Reverse(votes_against),
+                    },
+                };
+            },
+        );
+    }
+
+    fn schulze_path_matrix<T: nalgebra::Scalar + Copy + Ord + Default>(
+        overall_matrix: &nalgebra::DMatrix<T>,
+    ) -> nalgebra::DMatrix<T> {
         let num_candidates = overall_matrix.row(0).len();
 
         // Step 1: fill with one-step path preference? is what this is?
@@ -274,7 +801,7 @@ pub mod cv {
                 if overall_matrix[(runner, opponent)] > overall_matrix[(opponent, runner)] {
                     return overall_matrix[(runner, opponent)];
                 } else {
-                    return 0;
+                    return T::default();
                 }
             });
 
@@ -300,12 +827,14 @@ pub mod cv {
 
     fn schulze_ranking(
         overall_matrix: &nalgebra::DMatrix<u32>,
-    ) -> (Vec<(BallotChoice, u32)>, nalgebra::DMatrix<u32>) {
-        if overall_matrix.len() == 0 {
-            return (vec![], overall_matrix.clone());
+        strength: Strength,
+    ) -> SchulzeResult {
+        let links = link_matrix(overall_matrix, strength);
+        if overall_matrix.is_empty() {
+            return (vec![], links);
         }
 
-        let path_matrix = schulze_path_matrix(overall_matrix);
+        let path_matrix = schulze_path_matrix(&links);
 
         // let mut candidate_to_num_wins: BTreeMap<BallotChoice, u32> = BTreeMap::new();
         let mut candidate_with_num_wins: Vec<(BallotChoice, u32)> = Vec::new();
@@ -322,13 +851,392 @@ pub mod cv {
                     runner_wins += 1;
                 }
             }
-            candidate_with_num_wins.push((u32::try_from(runner).unwrap(), runner_wins));
+            candidate_with_num_wins.push((vm::option_id(runner), runner_wins));
         }
 
-        candidate_with_num_wins.sort_by(|a, b| b.1.cmp(&a.1));
+        candidate_with_num_wins.sort_by_key(|c| std::cmp::Reverse(c.1));
         return (candidate_with_num_wins, path_matrix);
     }
 
+    /// Schulze proportional ranking is exponential in the number of options.
+    pub const MAX_PROPORTIONAL_CHOICES: u32 = 12;
+
+    /// One place of a proportional ranking.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ProportionalPlace {
+        pub choice: BallotChoice,
+        /// Every candidate tied for this place, if there was a tie.
+        pub tied: Vec<BallotChoice>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ProportionalBreakdown {
+        /// Every candidate, best first.
+        pub places: Vec<ProportionalPlace>,
+        pub tie_breaker: TieBreaker,
+        /// The tie-breaking order of all candidates, if a tie had to be broken.
+        pub tie_break_order: Option<Vec<BallotChoice>>,
+    }
+
+    #[test]
+    fn test_proportional_strength() {
+        // Candidate positions for ballots 0 > 1 > 2 (x2) and 2 > 1 > 0.
+        let positions = vec![vec![0, 1, 2], vec![0, 1, 2], vec![2, 1, 0]];
+
+        // Without anyone placed yet it is a plain pairwise count.
+        assert_eq!(proportional_strength(&positions, &[], 0, 2), Ratio::from(2));
+        assert_eq!(proportional_strength(&positions, &[], 2, 0), Ratio::from(1));
+
+        // With 0 placed, {0, 1} against {0, 2}: the two voters preferring 1
+        // to 2 both also prefer 0 to 2, so each of 0 and 1 gets one.
+        assert_eq!(
+            proportional_strength(&positions, &[0], 1, 2),
+            Ratio::from(1)
+        );
+        // The voter preferring 2 to 1 does not prefer 0 to 1.
+        assert_eq!(
+            proportional_strength(&positions, &[0], 2, 1),
+            Ratio::from(0)
+        );
+    }
+
+    // The strength of the set `placed` + {c} against `placed` + {d} in Schulze
+    // STV: the largest t such that the voters who prefer c to d can give every
+    // member of the first set at least t, each voter spreading one vote among
+    // the members they prefer to d. By max-flow/min-cut that is the smallest
+    // ratio, over subsets S of the set, of voters preferring some member of S
+    // to d against the size of S.
+    fn proportional_strength(
+        positions: &[Vec<usize>],
+        placed: &[BallotChoice],
+        c: BallotChoice,
+        d: BallotChoice,
+    ) -> Ratio<u64> {
+        let position = |voter: &Vec<usize>, choice: BallotChoice| voter[vm::index(choice)];
+        let mut set = placed.to_vec();
+        set.push(c);
+
+        // For every voter preferring c to d, a bitmask of the set members
+        // they prefer to d.
+        let masks: Vec<u32> = positions
+            .iter()
+            .filter(|voter| position(voter, c) < position(voter, d))
+            .map(|voter| {
+                set.iter()
+                    .enumerate()
+                    .filter(|(_, member)| position(voter, **member) < position(voter, d))
+                    .fold(0, |mask, (i, _)| mask | (1 << i))
+            })
+            .collect();
+
+        return (1..(1u32 << set.len()))
+            .map(|subset| {
+                let supporters = masks.iter().filter(|mask| *mask & subset != 0).count();
+                Ratio::new(vm::count(supporters), u64::from(subset.count_ones()))
+            })
+            .min()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_schulze_proportional_ranking() {
+        let mut ballots: Vec<Ballot> = Vec::new();
+        ballots.extend(vec![Ballot::from([vec![0], vec![2], vec![3], vec![1]]); 3]);
+        ballots.extend(vec![Ballot::from([vec![1], vec![2], vec![0], vec![3]]); 3]);
+        ballots.push(Ballot::from([vec![0], vec![1], vec![3], vec![2]]));
+
+        // Plain Schulze ranks 0 > 1 > 2 > 3. Once 0 is placed, the voters who
+        // ranked 0 first are represented, so 2, which everyone ranked second,
+        // moves ahead of 1.
+        let result = vote(
+            4,
+            ballots.clone(),
+            &TieBreaker::None,
+            Strength::WinningVotes,
+        )
+        .unwrap();
+        assert_eq!(
+            vm::ranking_by_score(result.schulze_result.0),
+            vec![vec![0], vec![1], vec![2], vec![3]]
+        );
+        let result = schulze_proportional_ranking(4, &ballots, &TieBreaker::None).unwrap();
+        let queue: Vec<BallotChoice> = result.places.iter().map(|place| place.choice).collect();
+        assert_eq!(queue, vec![0, 2, 1, 3]);
+        assert!(result.places.iter().all(|place| place.tied.is_empty()));
+
+        // A tie for first is reported, and left to the lowest option ID
+        // without a tie-breaker.
+        let ballots = vec![
+            Ballot::from([vec![0], vec![1]]),
+            Ballot::from([vec![1], vec![0]]),
+        ];
+        let result = schulze_proportional_ranking(2, &ballots, &TieBreaker::None).unwrap();
+        assert_eq!(result.places[0].choice, 0);
+        assert_eq!(result.places[0].tied, vec![0, 1]);
+        let seeded = TieBreaker::RandomOrder { seed: 1 };
+        assert_eq!(
+            schulze_proportional_ranking(2, &ballots, &seeded).unwrap(),
+            schulze_proportional_ranking(2, &ballots, &seeded).unwrap()
+        );
+
+        assert!(matches!(
+            schulze_proportional_ranking(13, &ballots, &TieBreaker::None),
+            Err(VoteError::TooManyOptions { max: 12, .. })
+        ));
+    }
+
+    /// Schulze's proportional ranking: each place goes to the Schulze winner
+    /// among the remaining candidates, where candidates c and d are compared
+    /// by the Schulze STV strength of the already placed candidates plus c
+    /// against the already placed candidates plus d.
+    pub fn schulze_proportional_ranking(
+        num_choices: u32,
+        ballots: &[Ballot],
+        tie_breaker: &TieBreaker,
+    ) -> Result<ProportionalBreakdown, VoteError> {
+        if num_choices > MAX_PROPORTIONAL_CHOICES {
+            return Err(VoteError::TooManyOptions {
+                method: "Proportional ranking",
+                max: MAX_PROPORTIONAL_CHOICES,
+                num_choices,
+            });
+        }
+        outranking(num_choices, ballots)?;
+
+        let positions: Vec<Vec<usize>> = ballots
+            .iter()
+            .map(|ballot| {
+                (0..num_choices)
+                    .map(|choice| {
+                        ballot
+                            .iter()
+                            .position(|tier| tier.contains(&choice))
+                            .unwrap_or(usize::MAX)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut places: Vec<ProportionalPlace> = Vec::new();
+        let mut tie_order: Option<Vec<BallotChoice>> = None;
+        let mut placed: Vec<BallotChoice> = Vec::new();
+        while placed.len() < vm::index(num_choices) {
+            let remaining: Vec<BallotChoice> = (0..num_choices)
+                .filter(|choice| !placed.contains(choice))
+                .collect();
+            let strengths = nalgebra::DMatrix::from_fn(remaining.len(), remaining.len(), |c, d| {
+                if c == d {
+                    return Ratio::from(0);
+                }
+                return proportional_strength(&positions, &placed, remaining[c], remaining[d]);
+            });
+            let winners: Vec<BallotChoice> = schulze_winners(&schulze_path_matrix(&strengths))
+                .into_iter()
+                .map(|i| remaining[vm::index(i)])
+                .collect();
+
+            let choice = if winners.len() == 1 {
+                winners[0]
+            } else {
+                if tie_order.is_none() {
+                    tie_order =
+                        tie_break_order(ballots, &vec![1; ballots.len()], num_choices, tie_breaker);
+                }
+                match &tie_order {
+                    Some(order) => *order
+                        .iter()
+                        .find(|c| winners.contains(c))
+                        .unwrap_or(&winners[0]),
+                    None => winners[0],
+                }
+            };
+
+            placed.push(choice);
+            places.push(ProportionalPlace {
+                choice,
+                tied: if winners.len() > 1 { winners } else { vec![] },
+            });
+        }
+
+        return Ok(ProportionalBreakdown {
+            places,
+            tie_breaker: *tie_breaker,
+            tie_break_order: tie_order,
+        });
+    }
+
+    #[test]
+    fn test_schulze_winners() {
+        // Two candidates who beat everyone else but tie each other.
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 1, 2, //
+                1, 0, 2, //
+                0, 0, 0, //
+            ],
+        );
+        assert_eq!(schulze_winners(&schulze_path_matrix(&m)), vec![0, 1]);
+
+        let (_, path_matrix) = schulze_ranking(
+            &nalgebra::DMatrix::from_row_slice(
+                5,
+                5,
+                // https://en.wikipedia.org/wiki/Schulze_method
+                &[
+                    0, 20, 26, 30, 22, //
+                    25, 0, 16, 33, 18, //
+                    19, 29, 0, 17, 24, //
+                    15, 12, 28, 0, 14, //
+                    23, 27, 21, 31, 0, //
+                ],
+            ),
+            Strength::WinningVotes,
+        );
+        assert_eq!(schulze_winners(&path_matrix), vec![4]);
+    }
+
+    // The candidates that no one beats in the path matrix, i.e. every
+    // candidate that could be the Schulze winner.
+    fn schulze_winners<T: nalgebra::Scalar + Ord>(
+        path_matrix: &nalgebra::DMatrix<T>,
+    ) -> Vec<BallotChoice> {
+        let num_candidates = path_matrix.nrows();
+        return (0..num_candidates)
+            .filter(|runner| {
+                (0..num_candidates).all(|opponent| {
+                    path_matrix[(*runner, opponent)] >= path_matrix[(opponent, *runner)]
+                })
+            })
+            .map(vm::option_id)
+            .collect();
+    }
+
+    /// Every candidate tied for first by Schulze on `overall_matrix` under
+    /// `strength`, before any tie-breaking. A Condorcet winner is always the
+    /// only one.
+    pub fn winners(
+        overall_matrix: &nalgebra::DMatrix<u32>,
+        strength: Strength,
+    ) -> Vec<BallotChoice> {
+        return schulze_winners(&schulze_ranking(overall_matrix, strength).1);
+    }
+
+    #[test]
+    fn test_tie_break_order() {
+        let ballots = vec![
+            Ballot::from([vec![2], vec![0, 1]]),
+            Ballot::from([vec![2], vec![0, 1]]),
+        ];
+
+        // Every ballot puts 2 first and ties 0 and 1, so 2 always comes first
+        // and the seed only decides between 0 and 1.
+        for seed in 0..20 {
+            let order =
+                tie_break_order(&ballots, &[1, 1], 3, &TieBreaker::RandomBallot { seed }).unwrap();
+            assert_eq!(order[0], 2);
+        }
+
+        let order =
+            tie_break_order(&ballots, &[1, 1], 3, &TieBreaker::RandomOrder { seed: 7 }).unwrap();
+        assert_eq!(
+            Some(order),
+            tie_break_order(&ballots, &[1, 1], 3, &TieBreaker::RandomOrder { seed: 7 })
+        );
+        assert_eq!(
+            tie_break_order(&ballots, &[1, 1], 3, &TieBreaker::None),
+            None
+        );
+
+        // Drawn evenly, either ballot sometimes decides; drawn by weight, the
+        // heavy one almost always does.
+        let ballots = vec![
+            Ballot::from([vec![0], vec![1]]),
+            Ballot::from([vec![1], vec![0]]),
+        ];
+        let firsts = |units: &[u64]| -> Vec<BallotChoice> {
+            (0..20)
+                .map(|seed| {
+                    tie_break_order(&ballots, units, 2, &TieBreaker::RandomBallot { seed }).unwrap()
+                        [0]
+                })
+                .collect()
+        };
+        assert!(firsts(&[1, 1]).contains(&0));
+        assert!(firsts(&[1, 1]).contains(&1));
+        assert_eq!(firsts(&[1000, 1]), vec![0; 20]);
+    }
+
+    /// Splits every tied group of `ranking` in tie-breaking `order`.
+    pub fn break_ranking_ties(
+        ranking: Vec<Vec<BallotChoice>>,
+        order: &[BallotChoice],
+    ) -> Vec<Vec<BallotChoice>> {
+        return ranking
+            .into_iter()
+            .flat_map(|mut group| {
+                group.sort_by_key(|choice| order.iter().position(|c| c == choice));
+                group.into_iter().map(|choice| vec![choice])
+            })
+            .collect();
+    }
+
+    /// A strict order of every candidate to break ties with, or `None` for
+    /// `TieBreaker::None`. The random-ballot tie-breaker draws ballot i with
+    /// chance proportional to `units[i]`, its votes in `weighted_outranking`'s
+    /// vote units; give every ballot the same units to draw them evenly.
+    pub fn tie_break_order(
+        ballots: &[Ballot],
+        units: &[u64],
+        num_choices: u32,
+        tie_breaker: &TieBreaker,
+    ) -> Option<Vec<BallotChoice>> {
+        match tie_breaker {
+            TieBreaker::None => None,
+            TieBreaker::RandomOrder { seed } => {
+                let mut order: Vec<BallotChoice> = (0..num_choices).collect();
+                vm::SeededRng::new(*seed).shuffle(&mut order);
+                Some(order)
+            }
+            TieBreaker::RandomBallot { seed } => {
+                let mut rng = vm::SeededRng::new(*seed);
+                let mut draw_order: Vec<usize> = (0..ballots.len()).collect();
+                rng.weighted_shuffle(&mut draw_order, |i| units[*i]);
+
+                let mut groups: Vec<Vec<BallotChoice>> = vec![(0..num_choices).collect()];
+                for ballot_index in draw_order {
+                    if groups.iter().all(|group| group.len() == 1) {
+                        break;
+                    }
+
+                    let ballot = &ballots[ballot_index];
+                    let position = |choice: &BallotChoice| {
+                        ballot
+                            .iter()
+                            .position(|tier| tier.contains(choice))
+                            .unwrap_or(ballot.len())
+                    };
+                    groups = groups
+                        .into_iter()
+                        .flat_map(|group| {
+                            let mut split: BTreeMap<usize, Vec<BallotChoice>> = BTreeMap::new();
+                            for choice in group {
+                                split.entry(position(&choice)).or_default().push(choice);
+                            }
+                            split.into_values()
+                        })
+                        .collect();
+                }
+
+                for group in groups.iter_mut() {
+                    rng.shuffle(group);
+                }
+                Some(groups.concat())
+            }
+        }
+    }
+
     #[test]
     fn test_vote() {
         struct Case {
@@ -338,81 +1246,1047 @@ pub mod cv {
             expected_final_outranking: nalgebra::DMatrix<u32>,
         }
 
-        let cases = [Case {
-            num_choices: 4,
-            ballots: vec![
-                Ballot::from([1, 2, 0, 3]), // B, C, A, D
-                Ballot::from([3, 0, 2, 1]), // D, A, C, B
-                Ballot::from([0, 2, 1, 3]), // A, C, B, D
-            ],
-            expected_winner: Some(0),
-            expected_final_outranking: nalgebra::DMatrix::from_row_slice(
-                4,
-                4,
-                &[
-                    0, 2, 2, 2, //
-                    1, 0, 1, 2, //
-                    1, 2, 0, 2, //
-                    1, 1, 1, 0, //
+        let cases = [
+            Case {
+                num_choices: 4,
+                ballots: vec![
+                    Ballot::from([vec![1], vec![2], vec![0], vec![3]]), // B, C, A, D
+                    Ballot::from([vec![3], vec![0], vec![2], vec![1]]), // D, A, C, B
+                    Ballot::from([vec![0], vec![2], vec![1], vec![3]]), // A, C, B, D
                 ],
-            ),
-        }];
+                expected_winner: Some(0),
+                expected_final_outranking: nalgebra::DMatrix::from_row_slice(
+                    4,
+                    4,
+                    &[
+                        0, 2, 2, 2, //
+                        1, 0, 1, 2, //
+                        1, 2, 0, 2, //
+                        1, 1, 1, 0, //
+                    ],
+                ),
+            },
+            Case {
+                num_choices: 4,
+                ballots: vec![
+                    Ballot::from([vec![1], vec![2]]), // B, C
+                    Ballot::from([vec![3]]),          // D
+                    Ballot::from([vec![0], vec![2]]), // A, C
+                    Ballot::from([vec![2]]),          // C
+                ],
+                expected_winner: Some(2),
+                expected_final_outranking: nalgebra::DMatrix::from_row_slice(
+                    4,
+                    4,
+                    &[
+                        0, 1, 1, 1, //
+                        1, 0, 1, 1, //
+                        2, 2, 0, 3, //
+                        1, 1, 1, 0, //
+                    ],
+                ),
+            },
+            Case {
+                num_choices: 3,
+                ballots: vec![
+                    Ballot::from([vec![0, 1], vec![2]]),       // A = B, C
+                    Ballot::from([vec![1], vec![0], vec![2]]), // B, A, C
+                    Ballot::from([vec![2], vec![0, 1]]),       // C, A = B
+                ],
+                expected_winner: Some(1),
+                expected_final_outranking: nalgebra::DMatrix::from_row_slice(
+                    3,
+                    3,
+                    &[
+                        0, 0, 2, //
+                        1, 0, 2, //
+                        1, 1, 0, //
+                    ],
+                ),
+            },
+        ];
 
         for case in cases.iter() {
-            let result = vote(case.num_choices, case.ballots.clone()).unwrap();
+            let result = vote(
+                case.num_choices,
+                case.ballots.clone(),
+                &TieBreaker::None,
+                Strength::WinningVotes,
+            )
+            .unwrap();
             assert_eq!(result.final_outranking, case.expected_final_outranking);
             assert_eq!(result.winner, case.expected_winner,);
         }
+
+        assert_eq!(
+            vote(
+                2,
+                vec![Ballot::from([vec![0], vec![1], vec![2]])],
+                &TieBreaker::None,
+                Strength::WinningVotes
+            ),
+            Err(VoteError::BallotTooLong {
+                ballot: 0,
+                len: 3,
+                num_choices: 2
+            })
+        );
+        assert_eq!(
+            vote(
+                3,
+                vec![Ballot::from([vec![0], vec![3]])],
+                &TieBreaker::None,
+                Strength::WinningVotes
+            ),
+            Err(VoteError::UnknownOption {
+                ballot: 0,
+                choice: 3
+            })
+        );
+        assert_eq!(
+            vote(
+                3,
+                vec![Ballot::from([vec![0], vec![0]])],
+                &TieBreaker::None,
+                Strength::WinningVotes
+            ),
+            Err(VoteError::DuplicateRank {
+                ballot: 0,
+                choice: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_strongest_path() {
+        // https://en.wikipedia.org/wiki/Schulze_method
+        let d = nalgebra::DMatrix::from_row_slice(
+            5,
+            5,
+            &[
+                0, 20, 26, 30, 22, //
+                25, 0, 16, 33, 18, //
+                19, 29, 0, 17, 24, //
+                15, 12, 28, 0, 14, //
+                23, 27, 21, 31, 0, //
+            ],
+        );
+        let links = link_matrix(&d, Strength::WinningVotes);
+        let p = schulze_path_matrix(&links);
+
+        assert_eq!(strongest_path(&links, &p, 4, 0), Some(vec![4, 1, 0]));
+        assert_eq!(strongest_path(&links, &p, 4, 1), Some(vec![4, 3, 2, 1]));
+        assert_eq!(strongest_path(&links, &p, 4, 3), Some(vec![4, 3]));
+
+        let tied = link_matrix(
+            &nalgebra::DMatrix::from_row_slice(2, 2, &[0, 1, 1, 0]),
+            Strength::WinningVotes,
+        );
+        assert_eq!(
+            strongest_path(&tied, &schulze_path_matrix(&tied), 0, 1),
+            None
+        );
     }
 
-    pub fn vote(num_choices: u32, ballots: Vec<Ballot>) -> Result<VoteBreakdown, VoteError> {
-        if ballots.len() == 0 {
-            return Err(VoteError::new(&format!(
-                "Must have at least one ballot to vote"
-            )));
+    /// A shortest beatpath from `from` to `to` whose weakest link is as strong
+    /// as the path strength `path_matrix[(from, to)]`, following the wins in
+    /// `links` (see `link_matrix`). `None` if there is no beatpath at all.
+    pub fn strongest_path(
+        links: &nalgebra::DMatrix<Link>,
+        path_matrix: &nalgebra::DMatrix<Link>,
+        from: BallotChoice,
+        to: BallotChoice,
+    ) -> Option<Vec<BallotChoice>> {
+        let (from, to) = (vm::index(from), vm::index(to));
+        let strength = path_matrix[(from, to)];
+        if strength == Link::None {
+            return None;
         }
 
+        let num_candidates = links.nrows();
+        let mut previous: Vec<Option<usize>> = vec![None; num_candidates];
+        let mut queue = std::collections::VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to];
+                while let Some(before) = previous[*path.last().unwrap()] {
+                    path.push(before);
+                }
+                path.reverse();
+                return Some(path.into_iter().map(vm::option_id).collect());
+            }
+            for next in 0..num_candidates {
+                if next != from && previous[next].is_none() && links[(current, next)] >= strength {
+                    previous[next] = Some(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        return None;
+    }
+
+    #[test]
+    fn test_outranking() {
+        let ballots = vec![
+            Ballot::from([vec![1], vec![2], vec![0], vec![3]]),
+            Ballot::from([vec![2], vec![0]]),
+            Ballot::from([vec![1, 2], vec![0]]),
+            Ballot::new(),
+        ];
+        // One pass gives the sum of every ballot's own matrix.
+        let expected = ballots
+            .iter()
+            .map(|ballot| ballot_to_outranking_matrix(ballot, 4))
+            .reduce(|acc, matrix| acc + matrix)
+            .unwrap();
+        assert_eq!(outranking(4, &ballots).unwrap(), expected);
+
+        // Thousands of ballots.
+        let many: Vec<Ballot> = (0..3000)
+            .map(|i| Ballot::from([vec![i % 3], vec![(i + 1) % 3]]))
+            .collect();
+        let m = outranking(3, &many).unwrap();
+        assert_eq!(
+            (m[(0, 1)], m[(1, 0)], m[(0, 2)], m[(2, 0)]),
+            (2000, 1000, 1000, 2000)
+        );
+
+        assert_eq!(outranking(3, &[]), Err(VoteError::NoBallots));
+        assert_eq!(
+            outranking(
+                3,
+                &[Ballot::from([vec![1]]), Ballot::from([vec![0], vec![3]])]
+            ),
+            Err(VoteError::UnknownOption {
+                ballot: 1,
+                choice: 3
+            })
+        );
+    }
+
+    /// Validates `ballots` and sums their outranking matrices in one pass,
+    /// without building a matrix per ballot.
+    pub fn outranking(
+        num_choices: u32,
+        ballots: &[Ballot],
+    ) -> Result<nalgebra::DMatrix<u32>, VoteError> {
+        return counted_outranking(num_choices, ballots, |_| 1);
+    }
+
+    /// Like `outranking`, with ballot i counted `count(i)` times.
+    pub fn counted_outranking(
+        num_choices: u32,
+        ballots: &[Ballot],
+        count: impl Fn(usize) -> u64,
+    ) -> Result<nalgebra::DMatrix<u32>, VoteError> {
+        let overall_matrix = accumulate(num_choices, ballots, count)?;
+        return to_votes(overall_matrix).ok_or(VoteError::TooManyBallots);
+    }
+
+    /// The Tennessee capital election: Memphis 0, Nashville 1, Chattanooga 2,
+    /// Knoxville 3, from https://en.wikipedia.org/wiki/Borda_count#Example
+    #[cfg(test)]
+    pub fn tennessee_ballots() -> Vec<Ballot> {
+        let mut ballots: Vec<Ballot> = Vec::new();
+        ballots.extend(vec![vec![vec![0], vec![1], vec![2], vec![3]]; 42]);
+        ballots.extend(vec![vec![vec![1], vec![2], vec![3], vec![0]]; 26]);
+        ballots.extend(vec![vec![vec![2], vec![3], vec![1], vec![0]]; 15]);
+        ballots.extend(vec![vec![vec![3], vec![2], vec![1], vec![0]]; 17]);
+        return ballots;
+    }
+
+    /// Nine truncated ballots over four options, where Baldwin, Nanson and
+    /// minimax with winning votes or margins don't all agree.
+    #[cfg(test)]
+    pub fn truncated_ballots() -> Vec<Ballot> {
+        return vec![
+            vec![vec![2], vec![3], vec![1], vec![0]],
+            vec![vec![3], vec![1]],
+            vec![vec![3]],
+            vec![vec![1], vec![0], vec![2]],
+            vec![vec![0], vec![2]],
+            vec![vec![3]],
+            vec![vec![3], vec![0], vec![2], vec![1]],
+            vec![vec![1], vec![2], vec![3], vec![0]],
+            vec![vec![1], vec![0], vec![2]],
+        ];
+    }
+
+    // Validates `ballots` and adds `weight(i)` to the count of every pair
+    // ballot i ranks one way. Only a buffer of positions is kept per ballot.
+    fn accumulate(
+        num_choices: u32,
+        ballots: &[Ballot],
+        weight: impl Fn(usize) -> u64,
+    ) -> Result<nalgebra::DMatrix<u64>, VoteError> {
+        if ballots.is_empty() {
+            return Err(VoteError::NoBallots);
+        }
+
+        let num_candidates = vm::index(num_choices);
+        let mut overall_matrix = nalgebra::DMatrix::<u64>::zeros(num_candidates, num_candidates);
+        // Tier of each candidate on the current ballot, usize::MAX if unranked.
+        let mut position: Vec<usize> = vec![usize::MAX; num_candidates];
         for (i, ballot) in ballots.iter().enumerate() {
-            if u32::try_from(ballot.len()).unwrap() != num_choices {
-                return Err(VoteError::new(&format!(
-                    "Ballot {i} ({:?}) has an invalid number of choices",
-                    ballot
-                )));
+            let len = ballot.iter().flatten().count();
+            if len > num_candidates {
+                return Err(VoteError::BallotTooLong {
+                    ballot: i,
+                    len,
+                    num_choices,
+                });
+            }
+
+            position.fill(usize::MAX);
+            for (rank, tier) in ballot.iter().enumerate() {
+                for choice in tier.iter() {
+                    match position.get_mut(vm::index(*choice)) {
+                        None => {
+                            return Err(VoteError::UnknownOption {
+                                ballot: i,
+                                choice: *choice,
+                            });
+                        }
+                        Some(ranked) if *ranked != usize::MAX => {
+                            return Err(VoteError::DuplicateRank {
+                                ballot: i,
+                                choice: *choice,
+                            });
+                        }
+                        Some(ranked) => *ranked = rank,
+                    }
+                }
+            }
+
+            let weight = weight(i);
+            // Column by column, the order nalgebra stores them in.
+            for opponent in 0..num_candidates {
+                for runner in 0..num_candidates {
+                    if position[runner] < position[opponent] {
+                        overall_matrix[(runner, opponent)] =
+                            overall_matrix[(runner, opponent)].saturating_add(weight);
+                    }
+                }
+            }
+        }
+        return Ok(overall_matrix);
+    }
+
+    // The accumulated counts as the u32 matrix every method works on, or
+    // `None` if they do not fit.
+    fn to_votes(overall_matrix: nalgebra::DMatrix<u64>) -> Option<nalgebra::DMatrix<u32>> {
+        let votes: Vec<u32> = overall_matrix
+            .iter()
+            .map(|votes| u32::try_from(*votes).ok())
+            .collect::<Option<Vec<u32>>>()?;
+        return Some(nalgebra::DMatrix::from_vec(
+            overall_matrix.nrows(),
+            overall_matrix.ncols(),
+            votes,
+        ));
+    }
+
+    #[test]
+    fn test_weighted_outranking() {
+        let ballots = vec![
+            Ballot::from([vec![0], vec![1]]),
+            Ballot::from([vec![1], vec![0]]),
+            Ballot::from([vec![1], vec![0]]),
+        ];
+        let weights = [Ratio::from(2), Ratio::new(1, 2), Ratio::new(1, 3)];
+
+        // In sixths of a vote: 0 over 1 by 2, 1 over 0 by 1/2 + 1/3.
+        let (overall_matrix, vote_unit) = weighted_outranking(2, &ballots, &weights).unwrap();
+        assert_eq!(vote_unit, 6);
+        assert_eq!(
+            overall_matrix,
+            nalgebra::DMatrix::from_row_slice(2, 2, &[0, 12, 5, 0])
+        );
+
+        let (overall_matrix, vote_unit) =
+            weighted_outranking(2, &ballots, &[Ratio::from(1); 3]).unwrap();
+        assert_eq!(vote_unit, 1);
+        assert_eq!(overall_matrix, outranking(2, &ballots).unwrap());
+
+        assert_eq!(
+            weighted_outranking(2, &ballots, &weights[..2]),
+            Err(VoteError::WeightCount {
+                weights: 2,
+                ballots: 3
+            })
+        );
+        let too_fine = [
+            Ratio::new(1, 65521),
+            Ratio::new(1, 65519),
+            Ratio::new(1, 65497),
+        ];
+        assert_eq!(
+            weighted_outranking(2, &ballots, &too_fine),
+            Err(VoteError::WeightsTooFine)
+        );
+    }
+
+    /// Like `outranking`, but the sum counts each ballot `weights[i]` times.
+    /// To keep it in whole numbers it counts votes in units of 1/`vote_unit`,
+    /// the least common multiple of the weights' denominators, which is
+    /// returned with it. Every pairwise comparison is unaffected by the unit.
+    pub fn weighted_outranking(
+        num_choices: u32,
+        ballots: &[Ballot],
+        weights: &[Weight],
+    ) -> Result<(nalgebra::DMatrix<u32>, u32), VoteError> {
+        if weights.len() != ballots.len() {
+            return Err(VoteError::WeightCount {
+                weights: weights.len(),
+                ballots: ballots.len(),
+            });
+        }
+
+        let gcd = |mut a: u64, mut b: u64| {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            return a;
+        };
+        let mut vote_unit: u64 = 1;
+        for weight in weights.iter() {
+            let denom = u64::from(*weight.denom());
+            vote_unit = vote_unit / gcd(vote_unit, denom) * denom;
+            if vote_unit > u64::from(u32::MAX) {
+                return Err(VoteError::WeightsTooFine);
             }
         }
 
-        let outranking_matrices: Vec<nalgebra::DMatrix<u32>> = ballots
-            .clone()
+        let overall_matrix = accumulate(num_choices, ballots, |i| {
+            u64::from(*weights[i].numer()) * (vote_unit / u64::from(*weights[i].denom()))
+        })?;
+        return Ok((
+            to_votes(overall_matrix).ok_or(VoteError::WeightsTooFine)?,
+            u32::try_from(vote_unit).map_err(|_| VoteError::WeightsTooFine)?,
+        ));
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<Ballot>,
+        tie_breaker: &TieBreaker,
+        strength: Strength,
+    ) -> Result<VoteBreakdown, VoteError> {
+        let ballots = ballots
             .into_iter()
-            .map(|ballot| ballot_to_outranking_matrix(&ballot))
+            .map(|ballot| WeightedBallot {
+                weight: Weight::from(1),
+                ballot,
+            })
             .collect();
+        return weighted_vote(num_choices, ballots, tie_breaker, strength);
+    }
 
-        let overall_matrix = outranking_matrices
-            .clone()
+    #[test]
+    fn test_weighted_vote() {
+        // Two members of a household share a vote for 1; a single voter
+        // prefers 0.
+        let household = Ratio::new(1, 2);
+        let ballots = vec![
+            WeightedBallot {
+                weight: household,
+                ballot: Ballot::from([vec![1], vec![0]]),
+            },
+            WeightedBallot {
+                weight: household,
+                ballot: Ballot::from([vec![1], vec![0]]),
+            },
+            WeightedBallot {
+                weight: Ratio::from(1),
+                ballot: Ballot::from([vec![0], vec![1]]),
+            },
+        ];
+        let result =
+            weighted_vote(2, ballots.clone(), &TieBreaker::None, Strength::Margins).unwrap();
+        assert_eq!(result.winner, None);
+        assert_eq!(result.tied_winners, vec![0, 1]);
+        assert_eq!(result.vote_unit, 2);
+
+        // A proxy carrying two votes breaks the tie.
+        let mut ballots = ballots;
+        ballots.push(WeightedBallot {
+            weight: Ratio::from(2),
+            ballot: Ballot::from([vec![0], vec![1]]),
+        });
+        let result = weighted_vote(2, ballots, &TieBreaker::None, Strength::WinningVotes).unwrap();
+        assert_eq!(result.winner, Some(0));
+        assert_eq!(result.win_type, Some(WinType::CondorcetWinner));
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            explain(&result, &options),
+            "Dune beat every other option head-to-head; closest was Dune vs Emma, 3–1."
+        );
+
+        // A Schulze path with half votes.
+        let ballots = vec![
+            WeightedBallot {
+                weight: Ratio::new(3, 2),
+                ballot: Ballot::from([vec![0], vec![1], vec![2]]),
+            },
+            WeightedBallot {
+                weight: Ratio::from(1),
+                ballot: Ballot::from([vec![1], vec![2], vec![0]]),
+            },
+            WeightedBallot {
+                weight: Ratio::from(1),
+                ballot: Ballot::from([vec![2], vec![0], vec![1]]),
+            },
+        ];
+        let result = weighted_vote(3, ballots, &TieBreaker::None, Strength::WinningVotes).unwrap();
+        assert_eq!(result.winner, Some(0));
+        assert_eq!(
+            explain(&result, &options),
+            "Dune's strongest beatpath to each rival (link strength: winning votes):
+- Emma: Dune beat Emma 5/2–1 (strength 5/2; best path back: 2)
+- 2: Dune beat Emma 5/2–1, Emma beat 2 5/2–1 (strength 5/2; best path back: 2)"
+        );
+    }
+
+    /// Condorcet/Schulze on weighted ballots. Schulze's random-ballot
+    /// tie-breaker draws ballots in proportion to their weights.
+    pub fn weighted_vote(
+        num_choices: u32,
+        ballots: Vec<WeightedBallot>,
+        tie_breaker: &TieBreaker,
+        strength: Strength,
+    ) -> Result<VoteBreakdown, VoteError> {
+        let (ballots, weights): (Vec<Ballot>, Vec<Weight>) = ballots
             .into_iter()
-            .reduce(|acc, mat| acc + mat)
-            .unwrap();
+            .map(|weighted| (weighted.ballot, weighted.weight))
+            .unzip();
+        let (overall_matrix, vote_unit) = weighted_outranking(num_choices, &ballots, &weights)?;
+        let smith_set = smith_set(&overall_matrix);
+        let schwartz_set = schwartz_set(&overall_matrix);
+        let cycle = find_cycle(&overall_matrix, &smith_set);
+        let schulze_result = schulze_ranking(&overall_matrix, strength);
 
         if let Some(winner) = condorcet_winner(&overall_matrix) {
             return Ok(VoteBreakdown {
                 winner: Some(winner),
                 win_type: Some(WinType::CondorcetWinner),
+                tied_winners: vec![winner],
+                tie_breaker: *tie_breaker,
+                tie_break_order: None,
+                strength,
                 ballots,
-                ballot_outranking: outranking_matrices,
+                weights,
+                vote_unit,
                 final_outranking: overall_matrix,
-                schulze_result: None,
+                schulze_result,
+                smith_set,
+                schwartz_set,
+                cycle,
             });
         }
 
-        let schulze_result = schulze_ranking(&overall_matrix);
+        let tied_winners = schulze_winners(&schulze_result.1);
+
+        let (winner, tie_break_order) = if tied_winners.len() == 1 {
+            (Some(tied_winners[0]), None)
+        } else {
+            let units: Vec<u64> = weights
+                .iter()
+                .map(|weight| u64::from(*weight.numer()) * u64::from(vote_unit / *weight.denom()))
+                .collect();
+            match tie_break_order(&ballots, &units, num_choices, tie_breaker) {
+                Some(order) => (
+                    order
+                        .iter()
+                        .find(|choice| tied_winners.contains(choice))
+                        .copied(),
+                    Some(order),
+                ),
+                None => (None, None),
+            }
+        };
 
         return Ok(VoteBreakdown {
-            winner: Some(schulze_result.0.get(0).unwrap().0),
+            winner,
             win_type: Some(WinType::SchulzeRanking),
+            tied_winners,
+            tie_breaker: *tie_breaker,
+            tie_break_order,
+            strength,
             ballots,
-            ballot_outranking: outranking_matrices,
+            weights,
+            vote_unit,
             final_outranking: overall_matrix,
-            schulze_result: Some(schulze_result),
+            schulze_result,
+            smith_set,
+            schwartz_set,
+            cycle,
         });
     }
+
+    /// The Condorcet winner if there is one, otherwise the Schulze winner
+    /// with pairwise wins measured by `strength`.
+    pub struct Condorcet {
+        pub tie_breaker: TieBreaker,
+        pub strength: Strength,
+    }
+
+    impl vm::VotingMethod for Condorcet {
+        type Ballot = Ballot;
+        type Details = VoteBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            match self.strength {
+                Strength::WinningVotes => "Condorcet/Schulze",
+                Strength::Margins => "Condorcet/Schulze (margins)",
+                Strength::Ratio => "Condorcet/Schulze (ratio)",
+                Strength::Combined => "Condorcet/Schulze (combined)",
+            }
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, VoteError> {
+            let breakdown = vote(num_choices, ballots, &self.tie_breaker, self.strength)?;
+            return Ok(outcome(breakdown));
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let ties = tie_message(
+                &result.tied_winners,
+                &result.tie_breaker,
+                &result.tie_break_order,
+                option_id_to_option,
+            );
+            match (result.win_type.clone(), result.winner) {
+                (Some(WinType::CondorcetWinner), Some(winner)) => format!(
+                    "Condorcet winner (unambiguous): **{}**.\n{}",
+                    vm::option_name(winner, option_id_to_option),
+                    explain(result, option_id_to_option),
+                ),
+                (Some(WinType::SchulzeRanking), Some(winner)) => format!(
+                    "Winner via Schulze method: **{}**.{}{ties}\n{}",
+                    vm::option_name(winner, option_id_to_option),
+                    no_condorcet_winner_message(result, option_id_to_option),
+                    explain(result, option_id_to_option),
+                ),
+                (Some(WinType::SchulzeRanking), None) => format!(
+                    "No single winner via Schulze method.{}{ties}",
+                    no_condorcet_winner_message(result, option_id_to_option),
+                ),
+                (_, _) => format!("Unsupported vote result: {:?}", result),
+            }
+        }
+    }
+
+    // The winners and full ranking of a Condorcet/Schulze count.
+    fn outcome(breakdown: VoteBreakdown) -> vm::Outcome<VoteBreakdown> {
+        let ranking = vm::ranking_by_score(breakdown.schulze_result.0.iter().copied());
+        let ranking = match &breakdown.tie_break_order {
+            Some(order) => break_ranking_ties(ranking, order),
+            None => ranking,
+        };
+
+        let winners = match breakdown.winner {
+            Some(winner) => vec![winner],
+            None => breakdown.tied_winners.clone(),
+        };
+
+        return vm::Outcome {
+            winners,
+            ranking,
+            details: breakdown,
+        };
+    }
+
+    /// `Condorcet` on weighted ballots.
+    pub struct WeightedCondorcet {
+        pub tie_breaker: TieBreaker,
+        pub strength: Strength,
+    }
+
+    impl vm::VotingMethod for WeightedCondorcet {
+        type Ballot = WeightedBallot;
+        type Details = VoteBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            return self.unweighted().name();
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<WeightedBallot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, VoteError> {
+            let breakdown = weighted_vote(num_choices, ballots, &self.tie_breaker, self.strength)?;
+            return Ok(outcome(breakdown));
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let mut message = self.unweighted().describe(outcome, option_id_to_option);
+            let weights = &outcome.details.weights;
+            if weights.iter().any(|weight| *weight != Weight::from(1)) {
+                message += &format!(
+                    "\nBallot weights: {}",
+                    weights
+                        .iter()
+                        .map(|weight| weight.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+            }
+            return message;
+        }
+    }
+
+    impl WeightedCondorcet {
+        fn unweighted(&self) -> Condorcet {
+            return Condorcet {
+                tie_breaker: self.tie_breaker,
+                strength: self.strength,
+            };
+        }
+    }
+
+    /// Plain-English account of how the winner won, using option names: how
+    /// close the Condorcet winner came to losing, or the Schulze winner's
+    /// strongest beatpath to each rival.
+    pub fn explain(
+        result: &VoteBreakdown,
+        option_id_to_option: &BTreeMap<vm::OptionId, String>,
+    ) -> String {
+        let name = |choice: BallotChoice| vm::option_name(choice, option_id_to_option);
+        let votes = |runner: BallotChoice, opponent: BallotChoice| {
+            Ratio::new(
+                result.final_outranking[(vm::index(runner), vm::index(opponent))],
+                result.vote_unit,
+            )
+        };
+        let winner = match result.winner {
+            Some(winner) => winner,
+            None => return "".to_string(),
+        };
+        let num_choices = vm::option_id(result.final_outranking.nrows());
+        let rivals: Vec<BallotChoice> = (0..num_choices).filter(|c| *c != winner).collect();
+        if rivals.is_empty() {
+            return format!("{} was the only option.", name(winner));
+        }
+
+        let (_, path_matrix) = &result.schulze_result;
+        let links = link_matrix(&result.final_outranking, result.strength);
+        match result.win_type {
+            Some(WinType::CondorcetWinner) => {
+                let closest = rivals
+                    .iter()
+                    .min_by_key(|rival| votes(winner, **rival) - votes(**rival, winner))
+                    .unwrap();
+                return format!(
+                    "{} beat every other option head-to-head; closest was {} vs {}, {}–{}.",
+                    name(winner),
+                    name(winner),
+                    name(*closest),
+                    votes(winner, *closest),
+                    votes(*closest, winner),
+                );
+            }
+            Some(WinType::SchulzeRanking) => {
+                let mut lines = vec![format!(
+                    "{}'s strongest beatpath to each rival (link strength: {}):",
+                    name(winner),
+                    result.strength
+                )];
+                for rival in rivals {
+                    let strength = |from: BallotChoice, to: BallotChoice| {
+                        path_matrix[(vm::index(from), vm::index(to))].in_votes(result.vote_unit)
+                    };
+                    let path = strongest_path(&links, path_matrix, winner, rival);
+                    let steps = match path {
+                        Some(path) => path
+                            .windows(2)
+                            .map(|step| {
+                                format!(
+                                    "{} beat {} {}–{}",
+                                    name(step[0]),
+                                    name(step[1]),
+                                    votes(step[0], step[1]),
+                                    votes(step[1], step[0]),
+                                )
+                            })
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                        None => "no beatpath".to_string(),
+                    };
+                    lines.push(format!(
+                        "- {}: {steps} (strength {}; best path back: {})",
+                        name(rival),
+                        strength(winner, rival),
+                        strength(rival, winner),
+                    ));
+                }
+                return lines.join("\n");
+            }
+            None => return "".to_string(),
+        }
+    }
+
+    // Explains why a completion method was needed: who is in the Smith and
+    // Schwartz sets and a cycle among them.
+    fn no_condorcet_winner_message(
+        result: &VoteBreakdown,
+        option_id_to_option: &BTreeMap<vm::OptionId, String>,
+    ) -> String {
+        let names = |choices: &[BallotChoice]| {
+            choices
+                .iter()
+                .map(|choice| vm::option_label(*choice, option_id_to_option))
+                .collect::<Vec<String>>()
+        };
+
+        let mut message = format!(
+            "\nNo option beat every other head-to-head. Smith set: {}. Schwartz set: {}.",
+            names(&result.smith_set).join(", "),
+            names(&result.schwartz_set).join(", "),
+        );
+        if let Some(cycle) = &result.cycle {
+            let mut cycle = names(cycle);
+            cycle.push(cycle[0].clone());
+            message += &format!(" Cycle: {}.", cycle.join(" beats "));
+        }
+        return message;
+    }
+
+    /// Explains a tie for first place and how (or whether) it was broken.
+    /// Empty if there was no tie.
+    pub fn tie_message(
+        tied_winners: &[BallotChoice],
+        tie_breaker: &TieBreaker,
+        tie_break_order: &Option<Vec<BallotChoice>>,
+        option_id_to_option: &BTreeMap<vm::OptionId, String>,
+    ) -> String {
+        if tied_winners.len() < 2 {
+            return "".to_string();
+        }
+
+        let tied = tied_winners
+            .iter()
+            .map(|choice| vm::option_label(*choice, option_id_to_option))
+            .collect::<Vec<String>>()
+            .join(", ");
+        return match tie_break_order {
+            Some(order) => format!(
+                "\nTied for first: {tied}. Broken with {tie_breaker}; tie-breaking order: {}",
+                order
+                    .iter()
+                    .map(|choice| vm::option_label(*choice, option_id_to_option))
+                    .collect::<Vec<String>>()
+                    .join(" > "),
+            ),
+            None => format!("\nTied for first: {tied}. The tie was not broken."),
+        };
+    }
+
+    #[test]
+    fn test_tie_message() {
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(tie_message(&[0], &TieBreaker::None, &None, &options), "");
+        assert_eq!(
+            tie_message(
+                &[0, 1],
+                &TieBreaker::RandomOrder { seed: 7 },
+                &Some(vec![1, 0]),
+                &options
+            ),
+            "\nTied for first: 0 (Dune), 1 (Emma). Broken with random order (seed 7); tie-breaking order: 1 (Emma) > 0 (Dune)"
+        );
+        assert_eq!(
+            tie_message(&[0, 1], &TieBreaker::None, &None, &options),
+            "\nTied for first: 0 (Dune), 1 (Emma). The tie was not broken."
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let options = BTreeMap::from([
+            (0, "Dune".to_string()),
+            (1, "Emma".to_string()),
+            (2, "Moby".to_string()),
+        ]);
+
+        // Dune beats both Emma and Moby 4-3; ties go to the lower option ID.
+        let mut ballots: Vec<Ballot> = Vec::new();
+        ballots.extend(vec![Ballot::from([vec![0], vec![1], vec![2]]); 2]);
+        ballots.extend(vec![Ballot::from([vec![0], vec![2], vec![1]]); 2]);
+        ballots.extend(vec![Ballot::from([vec![1], vec![2], vec![0]]); 3]);
+        let result = vote(3, ballots, &TieBreaker::None, Strength::WinningVotes).unwrap();
+        assert_eq!(
+            explain(&result, &options),
+            "Dune beat every other option head-to-head; closest was Dune vs Emma, 4–3."
+        );
+
+        // Dune beats Emma 5-2, Emma beats Moby 5-2, Moby beats Dune 4-3.
+        let mut ballots: Vec<Ballot> = Vec::new();
+        ballots.extend(vec![Ballot::from([vec![0], vec![1], vec![2]]); 3]);
+        ballots.extend(vec![Ballot::from([vec![1], vec![2], vec![0]]); 2]);
+        ballots.extend(vec![Ballot::from([vec![2], vec![0], vec![1]]); 2]);
+        let result = vote(3, ballots, &TieBreaker::None, Strength::WinningVotes).unwrap();
+        assert_eq!(
+            explain(&result, &options),
+            "Dune's strongest beatpath to each rival (link strength: winning votes):
+- Emma: Dune beat Emma 5–2 (strength 5; best path back: 4)
+- Moby: Dune beat Emma 5–2, Emma beat Moby 5–2 (strength 5; best path back: 4)"
+        );
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            Ballot::from([vec![1], vec![2], vec![0], vec![3]]), // B, C, A, D
+            Ballot::from([vec![3], vec![0], vec![2], vec![1]]), // D, A, C, B
+            Ballot::from([vec![0], vec![2], vec![1], vec![3]]), // A, C, B, D
+        ];
+        let outcome = Condorcet {
+            tie_breaker: TieBreaker::None,
+            strength: Strength::WinningVotes,
+        }
+        .tally(4, ballots)
+        .unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        // The rest are ranked too, not just the Condorcet winner.
+        assert_eq!(outcome.ranking, vec![vec![0], vec![2], vec![1], vec![3]]);
+
+        // A perfect two-way tie.
+        let ballots = vec![
+            Ballot::from([vec![0], vec![1]]),
+            Ballot::from([vec![1], vec![0]]),
+        ];
+        let unbroken = Condorcet {
+            tie_breaker: TieBreaker::None,
+            strength: Strength::WinningVotes,
+        }
+        .tally(2, ballots.clone())
+        .unwrap();
+        assert_eq!(unbroken.winners, vec![0, 1]);
+        assert_eq!(unbroken.details.winner, None);
+        assert_eq!(unbroken.ranking, vec![vec![0, 1]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        for tie_breaker in [
+            TieBreaker::RandomBallot { seed: 3 },
+            TieBreaker::RandomOrder { seed: 3 },
+        ] {
+            let method = Condorcet {
+                tie_breaker,
+                strength: Strength::Margins,
+            };
+            let broken = method.tally(2, ballots.clone()).unwrap();
+            assert_eq!(broken.winners.len(), 1);
+            assert_eq!(broken.details.tied_winners, vec![0, 1]);
+            assert_eq!(broken.ranking.len(), 2);
+            assert_eq!(broken.ranking[0], broken.winners);
+            // Same seed, same result.
+            assert_eq!(method.tally(2, ballots.clone()).unwrap(), broken);
+            assert!(method.describe(&broken, &options).contains("seed 3"));
+            assert!(
+                method
+                    .describe(&broken, &options)
+                    .contains("Smith set: 0 (Dune), 1 (Emma).")
+            );
+        }
+    }
+
+    /// Schulze proportional ranking of every option, for ordering a queue.
+    pub struct SchulzeProportional {
+        pub tie_breaker: TieBreaker,
+    }
+
+    impl vm::VotingMethod for SchulzeProportional {
+        type Ballot = Ballot;
+        type Details = ProportionalBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            "Schulze proportional ranking"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+        ) -> Result<vm::Outcome<ProportionalBreakdown>, VoteError> {
+            let breakdown = schulze_proportional_ranking(num_choices, &ballots, &self.tie_breaker)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.places.iter().take(1).map(|p| p.choice).collect(),
+                ranking: breakdown.places.iter().map(|p| vec![p.choice]).collect(),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<ProportionalBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let mut message = "Queue via Schulze proportional ranking:".to_string();
+            for (i, place) in result.places.iter().enumerate() {
+                message += &format!(
+                    "\n{}. **{}**",
+                    i + 1,
+                    vm::option_name(place.choice, option_id_to_option)
+                );
+                if !place.tied.is_empty() {
+                    let others: Vec<String> = place
+                        .tied
+                        .iter()
+                        .filter(|choice| **choice != place.choice)
+                        .map(|choice| vm::option_name(*choice, option_id_to_option))
+                        .collect();
+                    let broken = match result.tie_break_order {
+                        Some(_) => format!("broken with {}", result.tie_breaker),
+                        None => "lowest option ID goes first".to_string(),
+                    };
+                    message += &format!(" (tied with {}; {broken})", others.join(", "));
+                }
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally_proportional() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            Ballot::from([vec![0], vec![1]]),
+            Ballot::from([vec![1], vec![0]]),
+        ];
+        let method = SchulzeProportional {
+            tie_breaker: TieBreaker::None,
+        };
+        let outcome = method.tally(2, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            method.describe(&outcome, &options),
+            "Queue via Schulze proportional ranking:
+1. **Dune** (tied with Emma; lowest option ID goes first)
+2. **Emma**"
+        );
+    }
 }
@@ -0,0 +1,230 @@
+pub mod copeland {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    /// An option's head-to-head results against every other option.
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub struct Record {
+        pub wins: u32,
+        pub ties: u32,
+        pub losses: u32,
+    }
+
+    impl Record {
+        /// Copeland points, doubled so a tie's half point stays whole.
+        pub fn double_points(&self) -> u32 {
+            2 * self.wins + self.ties
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Every option sharing the most points.
+        pub winners: Vec<cv::BallotChoice>,
+        pub records: BTreeMap<cv::BallotChoice, Record>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_records() {
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 5, 2, //
+                2, 0, 4, //
+                5, 4, 0, //
+            ],
+        );
+        assert_eq!(
+            records(&m),
+            BTreeMap::from([
+                (
+                    0,
+                    Record {
+                        wins: 1,
+                        ties: 0,
+                        losses: 1
+                    }
+                ),
+                (
+                    1,
+                    Record {
+                        wins: 0,
+                        ties: 1,
+                        losses: 1
+                    }
+                ),
+                (
+                    2,
+                    Record {
+                        wins: 1,
+                        ties: 1,
+                        losses: 0
+                    }
+                ),
+            ])
+        );
+    }
+
+    // Each option's wins, ties and losses in the summed outranking matrix.
+    fn records(final_outranking: &nalgebra::DMatrix<u32>) -> BTreeMap<cv::BallotChoice, Record> {
+        let num_choices = final_outranking.nrows();
+        let mut records = BTreeMap::new();
+        for runner in 0..num_choices {
+            let mut record = Record::default();
+            for opponent in (0..num_choices).filter(|opponent| *opponent != runner) {
+                let votes_for = final_outranking[(runner, opponent)];
+                let votes_against = final_outranking[(opponent, runner)];
+                match votes_for.cmp(&votes_against) {
+                    std::cmp::Ordering::Greater => record.wins += 1,
+                    std::cmp::Ordering::Equal => record.ties += 1,
+                    std::cmp::Ordering::Less => record.losses += 1,
+                }
+            }
+            records.insert(vm::option_id(runner), record);
+        }
+        return records;
+    }
+
+    #[test]
+    fn test_vote() {
+        // Pairwise results from
+        // https://en.wikipedia.org/wiki/Condorcet_method#Example
+        let ballots = cv::tennessee_ballots();
+
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.winners, vec![1]);
+        let wins: Vec<u32> = result.records.values().map(|record| record.wins).collect();
+        assert_eq!(wins, vec![0, 3, 2, 1]);
+
+        // A beats B beats C beats A: everyone has one win.
+        let mut ballots: Vec<cv::Ballot> = Vec::new();
+        ballots.extend(vec![vec![vec![0], vec![1], vec![2]]; 3]);
+        ballots.extend(vec![vec![vec![1], vec![2], vec![0]]; 2]);
+        ballots.extend(vec![vec![vec![2], vec![0], vec![1]]; 2]);
+        assert_eq!(vote(3, ballots).unwrap().winners, vec![0, 1, 2]);
+
+        assert!(vote(3, vec![]).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+        let records = records(&final_outranking);
+
+        let best = records
+            .values()
+            .map(Record::double_points)
+            .max()
+            .unwrap_or(0);
+        let winners = records
+            .iter()
+            .filter(|(_, record)| record.double_points() == best)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        return Ok(VoteBreakdown {
+            winners,
+            records,
+            final_outranking,
+        });
+    }
+
+    // Formats doubled points as e.g. "2" or "1.5".
+    fn points_str(double_points: u32) -> String {
+        match double_points % 2 {
+            0 => format!("{}", double_points / 2),
+            _ => format!("{}.5", double_points / 2),
+        }
+    }
+
+    /// Copeland's method: a point for every head-to-head win and half a
+    /// point for every head-to-head tie.
+    pub struct Copeland;
+
+    impl vm::VotingMethod for Copeland {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Copeland"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking: vm::ranking_by_score(
+                    breakdown
+                        .records
+                        .iter()
+                        .map(|(choice, record)| (*choice, record.double_points())),
+                ),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Copeland winner: {}.", winners[0]),
+                _ => format!("Tied Copeland winners: {}.", winners.join(", ")),
+            };
+
+            message += "\nHead-to-head records (wins-ties-losses):";
+            for (choice, record) in result.records.iter() {
+                message += &format!(
+                    "\n- {}: {}-{}-{}, {} points",
+                    vm::option_label(*choice, option_id_to_option),
+                    record.wins,
+                    record.ties,
+                    record.losses,
+                    points_str(record.double_points())
+                );
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        // 0 beats 2 and ties 1, 1 beats 2.
+        let ballots = vec![
+            cv::Ballot::from([vec![0], vec![1], vec![2]]),
+            cv::Ballot::from([vec![1], vec![0], vec![2]]),
+        ];
+        let outcome = Copeland.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0, 1]);
+        assert_eq!(outcome.ranking, vec![vec![0, 1], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            Copeland.describe(&outcome, &options),
+            "Tied Copeland winners: **Dune**, **Emma**.
+Head-to-head records (wins-ties-losses):
+- 0 (Dune): 1-1-0, 1.5 points
+- 1 (Emma): 1-1-0, 1.5 points
+- 2: 0-0-2, 0 points"
+        );
+    }
+}
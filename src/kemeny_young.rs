@@ -0,0 +1,343 @@
+pub mod kemeny {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    /// The exact search looks at up to `MAX_KEMENY_CHOICES`! orderings in
+    /// the worst case (every pairwise contest tied), so larger elections are
+    /// refused rather than left running.
+    pub const MAX_KEMENY_CHOICES: u32 = 10;
+    /// How many of the optimal rankings a breakdown lists.
+    pub const MAX_LISTED_RANKINGS: usize = 5;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Pairwise preferences on the ballots that agree with an optimal
+        /// ranking.
+        pub score: u64,
+        /// Up to `MAX_LISTED_RANKINGS` optimal rankings, in order of option ID.
+        pub optimal_rankings: Vec<Vec<cv::BallotChoice>>,
+        pub num_optimal: u64,
+        /// What every optimal ranking agrees on: an option is ranked above
+        /// another group only if it is above it in all of them.
+        pub ranking: Vec<Vec<cv::BallotChoice>>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_kemeny_score() {
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 5, 3, //
+                2, 0, 5, //
+                4, 2, 0, //
+            ],
+        );
+        assert_eq!(kemeny_score(&m, &[0, 1, 2]), 13);
+        assert_eq!(kemeny_score(&m, &[2, 0, 1]), 11);
+    }
+
+    /// Pairwise preferences on the ballots that agree with `ranking`.
+    pub fn kemeny_score(
+        final_outranking: &nalgebra::DMatrix<u32>,
+        ranking: &[cv::BallotChoice],
+    ) -> u64 {
+        let index = |choice: &cv::BallotChoice| vm::index(*choice);
+        let mut score = 0;
+        for (i, above) in ranking.iter().enumerate() {
+            for below in ranking[i + 1..].iter() {
+                score += u64::from(final_outranking[(index(above), index(below))]);
+            }
+        }
+        return score;
+    }
+
+    // Depth-first search over orderings, placing one option at a time.
+    struct Search<'a> {
+        final_outranking: &'a nalgebra::DMatrix<u32>,
+        num_choices: usize,
+        best_score: u64,
+        optimal_rankings: Vec<Vec<cv::BallotChoice>>,
+        num_optimal: u64,
+        // Bitmasks of the options in the first k places of the first optimal
+        // ranking found, and whether every later one placed the same options
+        // there.
+        prefix_masks: Vec<u32>,
+        same_prefix: Vec<bool>,
+    }
+
+    impl Search<'_> {
+        fn agreements(&self, above: usize, below: usize) -> u64 {
+            u64::from(self.final_outranking[(above, below)])
+        }
+
+        // The most agreements any order of the unplaced pairs could add.
+        fn best_case(&self, placed: u32) -> u64 {
+            let mut bound = 0;
+            for a in (0..self.num_choices).filter(|a| placed & (1 << a) == 0) {
+                for b in (a + 1..self.num_choices).filter(|b| placed & (1 << b) == 0) {
+                    bound += self.agreements(a, b).max(self.agreements(b, a));
+                }
+            }
+            return bound;
+        }
+
+        fn record(&mut self, prefix: &[cv::BallotChoice], score: u64) {
+            let masks: Vec<u32> = (0..=prefix.len())
+                .map(|k| {
+                    prefix[..k]
+                        .iter()
+                        .fold(0, |mask, choice| mask | (1 << choice))
+                })
+                .collect();
+            if score > self.best_score || self.num_optimal == 0 {
+                self.best_score = score;
+                self.optimal_rankings.clear();
+                self.num_optimal = 0;
+                self.same_prefix = vec![true; masks.len()];
+                self.prefix_masks = masks;
+            } else {
+                for (k, mask) in masks.iter().enumerate() {
+                    self.same_prefix[k] &= *mask == self.prefix_masks[k];
+                }
+            }
+
+            self.num_optimal += 1;
+            if self.optimal_rankings.len() < MAX_LISTED_RANKINGS {
+                self.optimal_rankings.push(prefix.to_vec());
+            }
+        }
+
+        fn extend(&mut self, prefix: &mut Vec<cv::BallotChoice>, placed: u32, score: u64) {
+            if prefix.len() == self.num_choices {
+                if score >= self.best_score {
+                    self.record(prefix, score);
+                }
+                return;
+            }
+            // Ties with the best score so far are explored, so every optimal
+            // ranking is found.
+            if score + self.best_case(placed) < self.best_score {
+                return;
+            }
+
+            for next in (0..self.num_choices).filter(|next| placed & (1 << next) == 0) {
+                let gained: u64 = (0..self.num_choices)
+                    .filter(|other| *other != next && placed & (1 << other) == 0)
+                    .map(|other| self.agreements(next, other))
+                    .sum();
+                prefix.push(vm::option_id(next));
+                self.extend(prefix, placed | (1 << next), score + gained);
+                prefix.pop();
+            }
+        }
+    }
+
+    // Starting point for pruning: options ordered by how many pairwise
+    // preferences favour them.
+    fn greedy_ranking(final_outranking: &nalgebra::DMatrix<u32>) -> Vec<cv::BallotChoice> {
+        let num_choices = final_outranking.nrows();
+        let mut ranking: Vec<usize> = (0..num_choices).collect();
+        ranking.sort_by_key(|choice| std::cmp::Reverse(final_outranking.row(*choice).sum()));
+        return ranking.into_iter().map(vm::option_id).collect();
+    }
+
+    #[test]
+    fn test_vote() {
+        // https://en.wikipedia.org/wiki/Kemeny%E2%80%93Young_method#Example
+        let ballots = cv::tennessee_ballots();
+
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.score, 393);
+        assert_eq!(result.optimal_rankings, vec![vec![1, 2, 3, 0]]);
+        assert_eq!(result.num_optimal, 1);
+        assert_eq!(result.ranking, vec![vec![1], vec![2], vec![3], vec![0]]);
+
+        // A beats B beats C beats A; breaking the weakest link is best.
+        let mut ballots: Vec<cv::Ballot> = Vec::new();
+        ballots.extend(vec![vec![vec![0], vec![1], vec![2]]; 3]);
+        ballots.extend(vec![vec![vec![1], vec![2], vec![0]]; 2]);
+        ballots.extend(vec![vec![vec![2], vec![0], vec![1]]; 2]);
+        let result = vote(3, ballots).unwrap();
+        assert_eq!(result.score, 13);
+        assert_eq!(result.optimal_rankings, vec![vec![0, 1, 2]]);
+
+        // 2 and 3 are tied on the only ballot, so two rankings are optimal
+        // and agree on everything but their order.
+        let result = vote(4, vec![vec![vec![0], vec![1], vec![2, 3]]]).unwrap();
+        assert_eq!(result.score, 5);
+        assert_eq!(
+            result.optimal_rankings,
+            vec![vec![0, 1, 2, 3], vec![0, 1, 3, 2]]
+        );
+        assert_eq!(result.num_optimal, 2);
+        assert_eq!(result.ranking, vec![vec![0], vec![1], vec![2, 3]]);
+
+        // Opposite ballots: every ranking is optimal.
+        let result = vote(
+            3,
+            vec![
+                vec![vec![0], vec![1], vec![2]],
+                vec![vec![2], vec![1], vec![0]],
+            ],
+        )
+        .unwrap();
+        assert_eq!(result.num_optimal, 6);
+        assert_eq!(result.optimal_rankings.len(), MAX_LISTED_RANKINGS);
+        assert_eq!(result.ranking, vec![vec![0, 1, 2]]);
+
+        assert_eq!(
+            vote(MAX_KEMENY_CHOICES + 1, vec![vec![vec![0]]]),
+            Err(cv::VoteError::TooManyOptions {
+                method: "Kemeny-Young",
+                max: MAX_KEMENY_CHOICES,
+                num_choices: MAX_KEMENY_CHOICES + 1
+            })
+        );
+        assert_eq!(vote(3, vec![]), Err(cv::VoteError::NoBallots));
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        if num_choices > MAX_KEMENY_CHOICES {
+            return Err(cv::VoteError::TooManyOptions {
+                method: "Kemeny-Young",
+                max: MAX_KEMENY_CHOICES,
+                num_choices,
+            });
+        }
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+
+        let greedy = greedy_ranking(&final_outranking);
+        let mut search = Search {
+            final_outranking: &final_outranking,
+            num_choices: vm::index(num_choices),
+            best_score: kemeny_score(&final_outranking, &greedy),
+            optimal_rankings: Vec::new(),
+            num_optimal: 0,
+            prefix_masks: Vec::new(),
+            same_prefix: Vec::new(),
+        };
+        search.extend(&mut Vec::new(), 0, 0);
+
+        // Split the first optimal ranking wherever all of them agree on
+        // which options come first.
+        let first = &search.optimal_rankings[0];
+        let mut ranking: Vec<Vec<cv::BallotChoice>> = Vec::new();
+        let mut start = 0;
+        for end in 1..=first.len() {
+            if search.same_prefix[end] {
+                let mut group = first[start..end].to_vec();
+                group.sort();
+                ranking.push(group);
+                start = end;
+            }
+        }
+
+        return Ok(VoteBreakdown {
+            score: search.best_score,
+            optimal_rankings: search.optimal_rankings,
+            num_optimal: search.num_optimal,
+            ranking,
+            final_outranking,
+        });
+    }
+
+    /// Kemeny-Young: the ranking agreeing with the most pairwise
+    /// preferences on the ballots.
+    pub struct KemenyYoung;
+
+    impl vm::VotingMethod for KemenyYoung {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Kemeny-Young"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.ranking.first().cloned().unwrap_or_default(),
+                ranking: breakdown.ranking.clone(),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let strict_str = |ranking: &Vec<cv::BallotChoice>| {
+                let groups: Vec<Vec<cv::BallotChoice>> =
+                    ranking.iter().map(|choice| vec![*choice]).collect();
+                vm::ranking_str(&groups, option_id_to_option)
+            };
+
+            let winners: Vec<String> = outcome
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Kemeny-Young winner: {}.", winners[0]),
+                _ => format!("Tied Kemeny-Young winners: {}.", winners.join(", ")),
+            };
+
+            match result.num_optimal {
+                1 => {
+                    message += &format!(
+                        "\nOptimal ranking: {} (score {}: pairwise preferences on the ballots it agrees with)",
+                        strict_str(&result.optimal_rankings[0]),
+                        result.score
+                    )
+                }
+                num_optimal => {
+                    message += &format!(
+                        "\n{num_optimal} rankings share the best score of {} (pairwise preferences on the ballots they agree with):",
+                        result.score
+                    );
+                    for ranking in result.optimal_rankings.iter() {
+                        message += &format!("\n- {}", strict_str(ranking));
+                    }
+                    let unlisted = num_optimal - vm::count(result.optimal_rankings.len());
+                    if unlisted > 0 {
+                        message += &format!("\n- ...and {unlisted} more");
+                    }
+                }
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let outcome = KemenyYoung
+            .tally(4, vec![vec![vec![0], vec![1], vec![2, 3]]])
+            .unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2, 3]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            KemenyYoung.describe(&outcome, &options),
+            "Kemeny-Young winner: **Dune**.
+2 rankings share the best score of 5 (pairwise preferences on the ballots they agree with):
+- Dune > Emma > 2 > 3
+- Dune > Emma > 3 > 2"
+        );
+    }
+}
@@ -4,10 +4,44 @@ use serenity::async_trait;
 use serenity::builder::CreateMessage;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::time::SystemTime;
+mod approval_voting;
+mod baldwin_method;
+mod borda_count;
 mod condorcet_voting;
+mod copeland_method;
+mod kemeny_young;
+mod majority_judgment;
+mod method_comparison;
+mod minimax_method;
+mod nanson_method;
+mod quadratic_voting;
+mod ranked_choice_voting;
+mod ranked_pairs;
+mod score_voting;
+mod sensitivity_analysis;
+mod single_transferable_vote;
+mod star_voting;
+mod voting_method;
+use crate::approval_voting::av;
+use crate::baldwin_method::baldwin;
+use crate::borda_count::borda;
 use crate::condorcet_voting::cv;
+use crate::copeland_method::copeland;
+use crate::kemeny_young::kemeny;
+use crate::majority_judgment::mj;
+use crate::method_comparison::compare;
+use crate::minimax_method::minimax;
+use crate::nanson_method::nanson;
+use crate::quadratic_voting::qv;
+use crate::ranked_choice_voting::rcv;
+use crate::ranked_pairs::rp;
+use crate::score_voting::sv;
+use crate::sensitivity_analysis::sensitivity;
+use crate::single_transferable_vote::stv;
+use crate::star_voting::star;
+use crate::voting_method::vm;
 
 struct Bot {
     database: sqlx::SqlitePool,
@@ -64,6 +98,46 @@ impl Bot {
         return option_to_option_id;
     }
 
+    async fn session_method(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        session_id: i64,
+    ) -> Option<SessionMethod> {
+        let row = sqlx::query!(
+            "SELECT method, qv_tokens, qv_negative, qv_max_votes, qv_banking, tie_breaker, tiebreak_seed, seats, strength FROM voting_session WHERE id = ?",
+            session_id,
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .unwrap();
+
+        let method = SessionMethod::from_row(
+            &row.method,
+            qv_rules_from_row(
+                row.qv_tokens,
+                row.qv_negative,
+                row.qv_max_votes,
+                row.qv_banking,
+            ),
+            &row.tie_breaker,
+            row.tiebreak_seed,
+            row.seats,
+            row.strength.as_deref(),
+        );
+        if method.is_none() {
+            error!(
+                "Session {session_id} has an unsupported method '{}' (qv_tokens: {:?}, tie_breaker: '{}', tiebreak_seed: {}, seats: {:?}, strength: {:?})",
+                row.method,
+                row.qv_tokens,
+                row.tie_breaker,
+                row.tiebreak_seed,
+                row.seats,
+                row.strength,
+            );
+        }
+        return method;
+    }
+
     async fn is_voting_complete(
         &self,
         vote_tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
@@ -92,30 +166,167 @@ SELECT COUNT(user) AS c FROM (
         return voters_left == 0;
     }
 
-    async fn finish_vote(
+    // Every stored ballot entry of a session with its voter.
+    async fn ballot_entries(
         &self,
-        vote_tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
-        ctx: &Context,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
         session_id: i64,
-        channel: ChannelId,
-    ) {
-        // FIXME: Close session after done.
-        // TODO: transaction
-
+    ) -> Vec<(String, BallotEntry)> {
         let votes = sqlx::query!(
             r#"
-SELECT voter, ballot_option_id, rank
+SELECT voter, ballot_option_id, rank, votes
 FROM session_votes
 WHERE session_id = ?
 GROUP BY session_id, voter, ballot_option_id, rank
-ORDER BY voter, rank ASC
+ORDER BY voter, rank, ballot_option_id ASC
 "#,
             session_id,
         )
-        .fetch_all(&mut **vote_tx)
+        .fetch_all(&mut **tx)
+        .await
+        .unwrap();
+
+        return votes
+            .into_iter()
+            .map(|row| {
+                (
+                    row.voter,
+                    BallotEntry {
+                        option_id: u32::try_from(row.ballot_option_id).unwrap(),
+                        rank: row.rank,
+                        votes: row.votes,
+                    },
+                )
+            })
+            .collect();
+    }
+
+    // Every participant's ballot weight, as set with `^weight`.
+    async fn participant_weights(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        session_id: i64,
+    ) -> BTreeMap<String, cv::Weight> {
+        let rows = sqlx::query!(
+            "SELECT participant, weight_numer, weight_denom FROM session_participants WHERE session_id = ?",
+            session_id,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .unwrap();
+
+        return rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.participant,
+                    cv::Weight::new(
+                        u32::try_from(row.weight_numer).unwrap(),
+                        u32::try_from(row.weight_denom).unwrap(),
+                    ),
+                )
+            })
+            .collect();
+    }
+
+    // The banked quadratic voting credits every participant brought to the
+    // session when voting started.
+    async fn participant_qv_credits(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        session_id: i64,
+    ) -> BTreeMap<String, i64> {
+        let rows = sqlx::query!(
+            "SELECT participant, qv_credits FROM session_participants WHERE session_id = ?",
+            session_id,
+        )
+        .fetch_all(&mut **tx)
         .await
         .unwrap();
 
+        return rows
+            .into_iter()
+            .map(|row| (row.participant, row.qv_credits))
+            .collect();
+    }
+
+    // Settles each participant's credits in the session's guild: the credits
+    // they brought are spent and the tokens they left unspent, as `rules`
+    // allow, are banked. Balances never drop below 0, even if the credits
+    // were also spent in another session meanwhile.
+    async fn bank_qv_credits(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        session_id: i64,
+        rules: &qv::Rules,
+        entries: &[(String, BallotEntry)],
+        credits: &BTreeMap<String, i64>,
+    ) -> Result<(), sqlx::Error> {
+        let guild = sqlx::query!(
+            "SELECT discord_server FROM voting_session WHERE id = ?",
+            session_id,
+        )
+        .fetch_one(&mut **tx)
+        .await?
+        .discord_server;
+
+        for (member, banked) in banked_credits(rules, entries, credits) {
+            let spent = credits.get(&member).copied().unwrap_or(0);
+            if banked == spent {
+                continue;
+            }
+            let change = banked - spent;
+            sqlx::query!(
+                "INSERT INTO qv_credits (discord_server, member, credits) VALUES (?, ?, ?)
+                   ON CONFLICT(discord_server, member) DO UPDATE SET credits = MAX(MIN(credits + ?, ?), 0)",
+                guild,
+                member,
+                banked,
+                change,
+                rules.tokens_per_ballot,
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        return Ok(());
+    }
+
+    // Records the guild credits every participant brings to the session. The
+    // ledger itself is only settled when the session finishes, so a session
+    // that is replaced or never finishes costs nobody their credits.
+    async fn reserve_qv_credits(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        guild: &str,
+        session_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE session_participants
+               SET qv_credits = COALESCE(
+                 (SELECT c.credits FROM qv_credits c
+                  WHERE c.discord_server = ? AND c.member = session_participants.participant),
+                 0)
+             WHERE session_id = ?",
+            guild,
+            session_id,
+        )
+        .execute(&mut **tx)
+        .await?;
+        return Ok(());
+    }
+
+    async fn finish_vote(
+        &self,
+        vote_tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        ctx: &Context,
+        session_id: i64,
+        channel: ChannelId,
+    ) -> Result<(), sqlx::Error> {
+        // FIXME: Close session after done.
+        // TODO: transaction
+
+        let entries = self.ballot_entries(vote_tx, session_id).await;
+
         sqlx::query!(
             "UPDATE voting_session SET status = 'finished' WHERE id = ?",
             session_id,
@@ -126,72 +337,137 @@ ORDER BY voter, rank ASC
 
         let option_to_option_id = self.option_to_option_id(vote_tx, session_id).await;
         let option_id_to_option = self.option_id_to_option(vote_tx, session_id).await;
-
-        let ballots_by_user: BTreeMap<String, cv::Ballot> = votes.iter().fold(
-            BTreeMap::new(),
-            |mut map: BTreeMap<String, cv::Ballot>, row| {
-                let option_id: u32 = u32::try_from(row.ballot_option_id).unwrap();
-                let voter: String = row.voter.clone();
-                let mut ballot: cv::Ballot = match map.get(&voter) {
-                    Some(ballot) => ballot.to_vec(),
-                    None => cv::Ballot::new(),
-                };
-                // TODO: Assert that ballot position matches row ordering
-                ballot.push(option_id);
-                map.insert(voter, ballot);
-                return map;
-            },
-        );
-        let ballots: Vec<cv::Ballot> = ballots_by_user.into_values().collect();
-        info!("Ballots for finish_vote: {:?}", ballots);
-
-        let result = cv::vote(u32::try_from(option_to_option_id.len()).unwrap(), ballots).unwrap();
-        let result_cloned = result.clone();
-
-        let response: String = match (
-            result_cloned.win_type,
-            result_cloned.winner,
-            result_cloned.final_outranking,
-            result_cloned.schulze_result,
-        ) {
-            (Some(cv::WinType::CondorcetWinner), Some(winner), overall_outranking, _) => {
-                // clean condorcet winner
-                info!("Win via condorcet. Result:\n{}", result);
-                format!(
-                    "Condorcet winner (unambiguous): {winner} - **{}**.
-Outranking matrix: ```{}```",
-                    option_id_to_option.get(&winner).unwrap(),
-                    overall_outranking,
-                )
-            }
-            (
-                Some(cv::WinType::SchulzeRanking),
-                Some(winner),
-                overall_outranking,
-                Some(schulze_result),
-            ) => {
-                let ranking_str_untrimmed: String = schulze_result
-                    .0
+        let num_choices = u32::try_from(option_to_option_id.len()).unwrap();
+
+        let response: String = match self.session_method(vote_tx, session_id).await {
+            Some(SessionMethod::Condorcet {
+                tie_breaker,
+                strength,
+            }) => tally_message(
+                &cv::WeightedCondorcet {
+                    tie_breaker,
+                    strength,
+                },
+                num_choices,
+                weighted_ranked_ballots(
+                    &entries,
+                    &self.participant_weights(vote_tx, session_id).await,
+                ),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::RankedPairs { tie_breaker }) => tally_message(
+                &rp::RankedPairs { tie_breaker },
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::ProportionalQueue { tie_breaker }) => tally_message(
+                &cv::SchulzeProportional { tie_breaker },
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::SingleTransferableVote { seats }) => tally_message(
+                &stv::SingleTransferableVote { seats },
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::InstantRunoff) => tally_message(
+                &rcv::InstantRunoff,
+                num_choices,
+                // Instant-runoff ballots never contain ties; see parse_ballot_entries.
+                ranked_ballots(&entries)
                     .into_iter()
-                    .fold("".to_string(), |acc: String, (choice, _)| {
-                        acc + &format!("{} > ", choice)
-                    });
-                let ranking_str = ranking_str_untrimmed
-                    .strip_suffix(" > ")
-                    .unwrap_or(&ranking_str_untrimmed);
-                info!(
-                    "Win via Schulze. Result:\n{}\nRanking: {ranking_str}",
-                    result,
-                );
-                format!(
-                    "Winner via Schulze method: {winner} - {}.\nSchulze ranking: {ranking_str}\nBase outranking matrix: ```{}```",
-                    option_id_to_option.get(&winner).unwrap(), overall_outranking,
-                )
+                    .map(|ballot| ballot.concat())
+                    .collect(),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Borda) => tally_message(
+                &borda::Borda,
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Copeland) => tally_message(
+                &copeland::Copeland,
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Minimax { strength }) => tally_message(
+                &minimax::Minimax { strength },
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Baldwin) => tally_message(
+                &baldwin::Baldwin,
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Nanson) => tally_message(
+                &nanson::Nanson,
+                num_choices,
+                ranked_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::KemenyYoung) => {
+                // The exact search can take seconds, so keep it off the
+                // message handler.
+                let ballots = ranked_ballots(&entries);
+                let options = option_id_to_option.clone();
+                tokio::task::spawn_blocking(move || {
+                    tally_message(&kemeny::KemenyYoung, num_choices, ballots, &options)
+                })
+                .await
+                .unwrap_or_else(|err| {
+                    error!("Failed to tally session {session_id}: {err}");
+                    "Failed to tally the vote.".to_string()
+                })
             }
-            (_, _, _, _) => {
-                error!("Unsupported vote result: {:?}", result);
-                format!("Unsupported vote result: {:?}", result)
+            Some(SessionMethod::Approval) => tally_message(
+                &av::ApprovalVoting,
+                num_choices,
+                approval_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Score) => tally_message(
+                &sv::ScoreVoting,
+                num_choices,
+                score_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Star) => tally_message(
+                &star::StarVoting,
+                num_choices,
+                score_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::MajorityJudgment) => tally_message(
+                &mj::MajorityJudgment,
+                num_choices,
+                mj_ballots(&entries),
+                &option_id_to_option,
+            ),
+            Some(SessionMethod::Quadratic { rules }) => {
+                let credits = self.participant_qv_credits(vote_tx, session_id).await;
+                let method = qv::QuadraticVoting { rules };
+                let ballots = qv_ballots(&entries, &credits);
+                // Credits are only settled for a vote that could be counted.
+                match tally_outcome(&method, num_choices, ballots, &option_id_to_option) {
+                    Ok(message) => {
+                        if rules.banking {
+                            self.bank_qv_credits(vote_tx, session_id, &rules, &entries, &credits)
+                                .await?;
+                        }
+                        message
+                    }
+                    Err(err) => tally_error(&method, err),
+                }
             }
+            None => "This session has an unsupported voting method.".to_string(),
         };
 
         match channel.say(&ctx, response).await {
@@ -200,6 +476,7 @@ Outranking matrix: ```{}```",
                 error!("Failed to send vote-result message: {err}");
             }
         };
+        return Ok(());
     }
 
     async fn latest_guild_session(
@@ -218,7 +495,7 @@ Outranking matrix: ```{}```",
             Ok(row) => return Some(row.id),
             Err(err) => {
                 info!("No open voting_sesssion found for server {guild}: {err}");
-                let response = format!("Failed to find an open voting session for your server. Try `^newsession`");
+                let response = "Failed to find an open voting session for your server. Try `^newsession`";
 
                 match channel_id.say(&ctx, response).await {
                     Ok(_) => {},
@@ -261,80 +538,238 @@ Outranking matrix: ```{}```",
 use std::error::Error;
 use std::fmt;
 
-// TODO: Better error...
-#[derive(Debug)]
-pub struct ParseError {
-    details: String,
-}
-
-impl ParseError {
-    fn new(msg: &str) -> ParseError {
-        ParseError {
-            details: msg.to_string(),
-        }
-    }
+/// Why a ballot or command argument could not be read. The message is sent
+/// back to whoever wrote it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// An entry that is not `option: value`. `kind` names the value, e.g.
+    /// "Score".
+    BadEntry {
+        kind: &'static str,
+        entry: String,
+    },
+    /// An option ID or vote count that is not a whole number.
+    NotANumber {
+        entry: String,
+        err: std::num::ParseIntError,
+    },
+    /// The same option twice on one ballot.
+    DuplicateOption {
+        entry: String,
+    },
+    /// A score above `sv::MAX_SCORE`.
+    ScoreTooHigh {
+        entry: String,
+    },
+    UnknownGrade {
+        grade: String,
+    },
+    /// An option ID that is not on the session's ballot.
+    UnknownOption(i64),
+    /// Options ranked equally on an instant-runoff ballot.
+    EqualRanks,
+    /// A quadratic ballot that breaks the session's rules.
+    InvalidQvBallot(qv::BallotError),
+    UnknownTieBreaker(String),
+    /// More than one Schulze link strength was given.
+    ConflictingStrengths,
+    UnknownDefeatStrength(String),
+    UnknownMethod(String),
+    /// An argument after a method that takes no more of them, e.g. `irv fast`.
+    UnexpectedArgument {
+        method: String,
+        arg: String,
+    },
+    InvalidSeats(String),
+    MissingSeats,
+    InvalidTokens(String),
+    MissingTokens,
+    /// A `^newsession qv` argument other than `positive`, `cap <n>` or
+    /// `bank`.
+    UnknownQvRule(String),
+    InvalidCap(String),
+    InvalidWeight(String),
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.details)
+        match self {
+            ParseError::BadEntry { kind, entry } => {
+                write!(f, "{kind} '{entry}' is an invalid format")
+            }
+            ParseError::NotANumber { entry, err } => {
+                write!(f, "'{entry}' could not be parsed as a whole number: {err}")
+            }
+            ParseError::DuplicateOption { entry } => write!(
+                f,
+                "Bad ballot: '{entry}' lists an option that is already on it"
+            ),
+            ParseError::ScoreTooHigh { entry } => write!(
+                f,
+                "Score '{entry}' is above the maximum of {}",
+                sv::MAX_SCORE
+            ),
+            ParseError::UnknownGrade { grade } => {
+                let names: Vec<String> = mj::Grade::ALL.iter().map(mj::Grade::to_string).collect();
+                write!(f, "Grade '{grade}' is not one of {}", names.join(", "))
+            }
+            ParseError::UnknownOption(choice) => {
+                write!(f, "Your ballot contains in invalid key: {choice}")
+            }
+            ParseError::EqualRanks => {
+                write!(f, "Instant-runoff ballots cannot rank options equally")
+            }
+            ParseError::InvalidQvBallot(err) => write!(f, "{err}"),
+            ParseError::UnknownTieBreaker(tie_breaker) => write!(
+                f,
+                "Unknown tie-breaker '{tie_breaker}'. Options: `ballot`, `random`, `none`"
+            ),
+            ParseError::ConflictingStrengths => write!(
+                f,
+                "Choose one link strength: `wv`, `margins`, `ratio` or `combined`"
+            ),
+            ParseError::UnknownDefeatStrength(strength) => write!(
+                f,
+                "Unknown defeat strength '{strength}'. Options: `wv`, `margins`"
+            ),
+            ParseError::UnknownMethod(method) => write!(
+                f,
+                "Unknown voting method '{method}'. Options: `cv [ballot | random | none] [wv | margins | ratio | combined]`, `rp [ballot | random | none]`, `queue [ballot | random | none]`, `irv`, `stv <seats>`, `borda`, `copeland`, `minimax [wv | margins]`, `baldwin`, `nanson`, `kemeny`, `approval`, `score`, `star`, `mj`, `qv <tokens> [positive] [cap <n>] [bank]`"
+            ),
+            ParseError::UnexpectedArgument { method, arg } => write!(
+                f,
+                "Unexpected argument '{arg}' for voting method `{method}`"
+            ),
+            ParseError::InvalidSeats(seats) => {
+                write!(f, "Seat count '{seats}' must be a positive integer")
+            }
+            ParseError::MissingSeats => write!(
+                f,
+                "Single transferable vote sessions need a seat count, e.g. `^newsession stv 3`"
+            ),
+            ParseError::InvalidTokens(tokens) => {
+                write!(
+                    f,
+                    "Token budget '{tokens}' must be a whole number from 1 to {}",
+                    qv::MAX_TOKENS
+                )
+            }
+            ParseError::MissingTokens => write!(
+                f,
+                "Quadratic sessions need a token budget, e.g. `^newsession qv 100`"
+            ),
+            ParseError::UnknownQvRule(rule) => write!(
+                f,
+                "Unknown quadratic voting rule '{rule}'. Options: `positive`, `cap <n>`, `bank`"
+            ),
+            ParseError::InvalidCap(cap) => {
+                write!(f, "Vote cap '{cap}' must be a positive integer")
+            }
+            ParseError::InvalidWeight(weight) => write!(
+                f,
+                "Weight '{weight}' must be a positive whole number or fraction like `1/2`, with parts up to {MAX_WEIGHT_TERM}"
+            ),
+        }
     }
 }
 
 impl Error for ParseError {
-    fn description(&self) -> &str {
-        &self.details
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::NotANumber { err, .. } => Some(err),
+            ParseError::InvalidQvBallot(err) => Some(err),
+            _ => None,
+        }
     }
 }
 
-// TODO: Better error...
+/// Why a member could not join a session.
 #[derive(Debug)]
-pub struct ParticipateError {
-    details: String,
-}
-
-impl ParticipateError {
-    fn new(msg: &str) -> ParticipateError {
-        ParticipateError {
-            details: msg.to_string(),
-        }
-    }
+pub enum ParticipateError {
+    Database(sqlx::Error),
 }
 
 impl fmt::Display for ParticipateError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.details)
+        match self {
+            ParticipateError::Database(err) => write!(f, "Failed to add participant: {err}"),
+        }
     }
 }
 
 impl Error for ParticipateError {
-    fn description(&self) -> &str {
-        &self.details
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParticipateError::Database(err) => Some(err),
+        }
     }
 }
 
+// Tallies `ballots` with `method` and builds the results post.
+fn tally_message<M: vm::VotingMethod>(
+    method: &M,
+    num_choices: u32,
+    ballots: Vec<M::Ballot>,
+    option_id_to_option: &BTreeMap<u32, String>,
+) -> String {
+    return tally_outcome(method, num_choices, ballots, option_id_to_option)
+        .unwrap_or_else(|err| tally_error(method, err));
+}
+
+// The results post for `ballots`, or why they could not be counted.
+fn tally_outcome<M: vm::VotingMethod>(
+    method: &M,
+    num_choices: u32,
+    ballots: Vec<M::Ballot>,
+    option_id_to_option: &BTreeMap<u32, String>,
+) -> Result<String, M::Error> {
+    info!("Ballots for {} vote: {:?}", method.name(), ballots);
+
+    let outcome = method.tally(num_choices, ballots)?;
+    info!("{} vote result: {:?}", method.name(), outcome);
+    return Ok(format!(
+        "{}\nFull ranking: {}",
+        method.describe(&outcome, option_id_to_option),
+        vm::ranking_str(&outcome.ranking, option_id_to_option),
+    ));
+}
+
+fn tally_error<M: vm::VotingMethod>(method: &M, err: M::Error) -> String {
+    error!("Failed to tally {} vote: {err}", method.name());
+    return format!("Failed to tally the vote: {err}");
+}
+
+// Parses e.g. `3 = 1 > 2`: tiers separated by `>`, equally ranked options
+// within a tier separated by `=`.
 fn parse_cv_ballot(ballot_str: String) -> Result<cv::Ballot, ParseError> {
     let mut ballot: cv::Ballot = Vec::new();
-    let raw_choices = ballot_str.trim().split(">");
-    for choice_raw in raw_choices.into_iter() {
-        let choice_parsed = match choice_raw.trim().parse::<u32>() {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(ParseError::new(&format!(
-                    "Vote '{choice_raw}' could not be parsed to u32: {err}",
-                )));
-            }
-        };
-        ballot.push(choice_parsed);
+    let raw_tiers = ballot_str.trim().split(">");
+    for tier_raw in raw_tiers.into_iter() {
+        let mut tier: Vec<cv::BallotChoice> = Vec::new();
+        for choice_raw in tier_raw.split("=") {
+            let choice_parsed = match choice_raw.trim().parse::<u32>() {
+                Ok(r) => r,
+                Err(err) => {
+                    return Err(ParseError::NotANumber {
+                        entry: choice_raw.trim().to_string(),
+                        err,
+                    });
+                }
+            };
+            tier.push(choice_parsed);
+        }
+        ballot.push(tier);
     }
 
-    let mut ballot_sorted: cv::Ballot = ballot.clone();
-    ballot_sorted.sort();
-    ballot_sorted.dedup();
-    if ballot_sorted.len() != ballot.len() {
-        return Err(ParseError::new(
-            &format!("Bad ballot: duplicates detected",),
-        ));
+    let mut seen: BTreeSet<cv::BallotChoice> = BTreeSet::new();
+    if let Some(repeated) = ballot
+        .iter()
+        .flatten()
+        .find(|choice| !seen.insert(**choice))
+    {
+        return Err(ParseError::DuplicateOption {
+            entry: repeated.to_string(),
+        });
     }
 
     return Ok(ballot);
@@ -350,17 +785,1656 @@ fn test_parse_cv_ballot() {
     let cases = [
         Case {
             input: "1 > 3 > 2".to_string(),
-            expected: cv::Ballot::from([1, 3, 2]),
+            expected: cv::Ballot::from([vec![1], vec![3], vec![2]]),
         },
         Case {
             input: "  3 > 2 >1  \n".to_string(),
-            expected: cv::Ballot::from([3, 2, 1]),
+            expected: cv::Ballot::from([vec![3], vec![2], vec![1]]),
+        },
+        Case {
+            input: "3 = 1 > 2".to_string(),
+            expected: cv::Ballot::from([vec![3, 1], vec![2]]),
+        },
+        Case {
+            input: "0=4=2".to_string(),
+            expected: cv::Ballot::from([vec![0, 4, 2]]),
+        },
+    ];
+
+    for case in cases.iter() {
+        assert_eq!(parse_cv_ballot(case.input.clone()).unwrap(), case.expected,);
+    }
+
+    for bad in ["3 = 3 > 2", "3 > 1 = 3", "3 = > 2", "3 >> 2"] {
+        assert!(parse_cv_ballot(bad.to_string()).is_err(), "{bad}");
+    }
+    assert_eq!(
+        parse_cv_ballot("3 > 1 = 3".to_string()),
+        Err(ParseError::DuplicateOption {
+            entry: "3".to_string()
+        })
+    );
+}
+
+fn parse_qv_ballot(ballot_str: String) -> Result<qv::Ballot, ParseError> {
+    ballot_str
+        .trim()
+        .split(",")
+        .try_fold(BTreeMap::new(), |mut map, vote_raw| {
+            let split = vote_raw.split(":").collect::<Vec<&str>>();
+            if split.len() != 2 {
+                return Err(ParseError::BadEntry {
+                    kind: "Vote",
+                    entry: vote_raw.trim().to_string(),
+                });
+            }
+            let ballot_key = match split[0].trim().parse::<i64>() {
+                Ok(x) => x,
+                Err(err) => {
+                    return Err(ParseError::NotANumber {
+                        entry: split[0].trim().to_string(),
+                        err,
+                    });
+                }
+            };
+            let ballot_votes = match split[1].trim().parse::<i64>() {
+                Ok(x) => x,
+                Err(err) => {
+                    return Err(ParseError::NotANumber {
+                        entry: split[1].trim().to_string(),
+                        err,
+                    });
+                }
+            };
+
+            if map.contains_key(&ballot_key) {
+                return Err(ParseError::DuplicateOption {
+                    entry: vote_raw.trim().to_string(),
+                });
+            }
+
+            map.insert(ballot_key, ballot_votes);
+            Ok(map)
+        })
+}
+
+#[test]
+fn test_parse_qv_ballot() {
+    struct Case {
+        input: String,
+        expected: qv::Ballot,
+    }
+
+    let cases = [
+        Case {
+            input: "1: 3, 4: 6, 9: 100".to_string(),
+            expected: qv::Ballot::from([(1, 3), (4, 6), (9, 100)]),
+        },
+        Case {
+            input: " 0:-2,2 : 1\n".to_string(),
+            expected: qv::Ballot::from([(0, -2), (2, 1)]),
+        },
+    ];
+
+    for case in cases.iter() {
+        assert_eq!(parse_qv_ballot(case.input.clone()).unwrap(), case.expected,);
+    }
+
+    for bad in ["1: 3, 1: 2", "1 3", "a: 3", ""] {
+        assert!(parse_qv_ballot(bad.to_string()).is_err(), "{bad}");
+    }
+}
+
+// Parses e.g. `1, 4, 5`: every option the voter approves of.
+fn parse_approval_ballot(ballot_str: String) -> Result<av::Ballot, ParseError> {
+    let mut ballot = av::Ballot::new();
+    for choice_raw in ballot_str.trim().split(",") {
+        let choice = match choice_raw.trim().parse::<u32>() {
+            Ok(choice) => choice,
+            Err(err) => {
+                return Err(ParseError::NotANumber {
+                    entry: choice_raw.trim().to_string(),
+                    err,
+                });
+            }
+        };
+        if !ballot.insert(choice) {
+            return Err(ParseError::DuplicateOption {
+                entry: choice_raw.trim().to_string(),
+            });
+        }
+    }
+    return Ok(ballot);
+}
+
+#[test]
+fn test_parse_approval_ballot() {
+    struct Case {
+        input: String,
+        expected: av::Ballot,
+    }
+
+    let cases = [
+        Case {
+            input: "1, 4, 5".to_string(),
+            expected: av::Ballot::from([1, 4, 5]),
+        },
+        Case {
+            input: " 2 \n".to_string(),
+            expected: av::Ballot::from([2]),
+        },
+    ];
+
+    for case in cases.iter() {
+        assert_eq!(
+            parse_approval_ballot(case.input.clone()).unwrap(),
+            case.expected,
+        );
+    }
+
+    for bad in ["1, 1", "1 4", "a", "", "1,,2"] {
+        assert!(parse_approval_ballot(bad.to_string()).is_err(), "{bad}");
+    }
+}
+
+// Parses e.g. `1: 5, 4: 3`: scores from 0 to `sv::MAX_SCORE` by option.
+fn parse_score_ballot(ballot_str: String) -> Result<sv::Ballot, ParseError> {
+    let mut ballot = sv::Ballot::new();
+    for score_raw in ballot_str.trim().split(",") {
+        let split = score_raw.split(":").collect::<Vec<&str>>();
+        if split.len() != 2 {
+            return Err(ParseError::BadEntry {
+                kind: "Score",
+                entry: score_raw.trim().to_string(),
+            });
+        }
+        let (choice, score) = match (
+            split[0].trim().parse::<u32>(),
+            split[1].trim().parse::<u32>(),
+        ) {
+            (Ok(choice), Ok(score)) => (choice, score),
+            (_, _) => {
+                return Err(ParseError::BadEntry {
+                    kind: "Score",
+                    entry: score_raw.trim().to_string(),
+                });
+            }
+        };
+        if score > sv::MAX_SCORE {
+            return Err(ParseError::ScoreTooHigh {
+                entry: score_raw.trim().to_string(),
+            });
+        }
+        if ballot.insert(choice, score).is_some() {
+            return Err(ParseError::DuplicateOption {
+                entry: score_raw.trim().to_string(),
+            });
+        }
+    }
+    return Ok(ballot);
+}
+
+#[test]
+fn test_parse_score_ballot() {
+    struct Case {
+        input: String,
+        expected: sv::Ballot,
+    }
+
+    let cases = [
+        Case {
+            input: "1: 5, 4: 3".to_string(),
+            expected: sv::Ballot::from([(1, 5), (4, 3)]),
+        },
+        Case {
+            input: " 0:0,2 : 1\n".to_string(),
+            expected: sv::Ballot::from([(0, 0), (2, 1)]),
         },
     ];
 
-    for case in cases.iter() {
-        assert_eq!(parse_cv_ballot(case.input.clone()).unwrap(), case.expected,);
-    }
+    for case in cases.iter() {
+        assert_eq!(
+            parse_score_ballot(case.input.clone()).unwrap(),
+            case.expected,
+        );
+    }
+
+    for bad in ["1: 3, 1: 2", "1 3", "a: 3", "", "1: 6", "1: -1"] {
+        assert!(parse_score_ballot(bad.to_string()).is_err(), "{bad}");
+    }
+}
+
+// Parses e.g. `1: Great, 4: OK`: a grade by option, ignoring case.
+fn parse_mj_ballot(ballot_str: String) -> Result<mj::Ballot, ParseError> {
+    let mut ballot = mj::Ballot::new();
+    for grade_raw in ballot_str.trim().split(",") {
+        let split = grade_raw.split(":").collect::<Vec<&str>>();
+        if split.len() != 2 {
+            return Err(ParseError::BadEntry {
+                kind: "Grade",
+                entry: grade_raw.trim().to_string(),
+            });
+        }
+        let choice = match split[0].trim().parse::<u32>() {
+            Ok(choice) => choice,
+            Err(err) => {
+                return Err(ParseError::NotANumber {
+                    entry: split[0].trim().to_string(),
+                    err,
+                });
+            }
+        };
+        let grade = match mj::Grade::parse(split[1]) {
+            Some(grade) => grade,
+            None => {
+                return Err(ParseError::UnknownGrade {
+                    grade: split[1].trim().to_string(),
+                });
+            }
+        };
+        if ballot.insert(choice, grade).is_some() {
+            return Err(ParseError::DuplicateOption {
+                entry: grade_raw.trim().to_string(),
+            });
+        }
+    }
+    return Ok(ballot);
+}
+
+#[test]
+fn test_parse_mj_ballot() {
+    struct Case {
+        input: String,
+        expected: mj::Ballot,
+    }
+
+    let cases = [
+        Case {
+            input: "1: Great, 4: OK".to_string(),
+            expected: mj::Ballot::from([(1, mj::Grade::Great), (4, mj::Grade::Ok)]),
+        },
+        Case {
+            input: " 0:reject,2 : gOOd\n".to_string(),
+            expected: mj::Ballot::from([(0, mj::Grade::Reject), (2, mj::Grade::Good)]),
+        },
+    ];
+
+    for case in cases.iter() {
+        assert_eq!(parse_mj_ballot(case.input.clone()).unwrap(), case.expected,);
+    }
+
+    for bad in [
+        "1: Good, 1: Poor",
+        "1 Good",
+        "a: Good",
+        "1: Fine",
+        "1: 3",
+        "",
+    ] {
+        assert!(parse_mj_ballot(bad.to_string()).is_err(), "{bad}");
+    }
+}
+
+/// How the ballots of a session are cast and tallied. Stored on
+/// `voting_session` so a session survives a bot restart.
+#[derive(Debug, Clone, PartialEq)]
+enum SessionMethod {
+    /// Ranked ballots, Condorcet winner with a Schulze fallback measuring
+    /// pairwise wins by `strength`.
+    Condorcet {
+        tie_breaker: cv::TieBreaker,
+        strength: cv::Strength,
+    },
+    /// Ranked ballots, Ranked Pairs (Tideman).
+    RankedPairs { tie_breaker: cv::TieBreaker },
+    /// Ranked ballots, instant-runoff voting.
+    InstantRunoff,
+    /// Ranked ballots, Schulze proportional ranking of every option.
+    ProportionalQueue { tie_breaker: cv::TieBreaker },
+    /// Ranked ballots, single transferable vote filling `seats` seats.
+    SingleTransferableVote { seats: u32 },
+    /// Ranked ballots, Borda count.
+    Borda,
+    /// Ranked ballots, Copeland's method.
+    Copeland,
+    /// Ranked ballots, Minimax with the given defeat strength.
+    Minimax { strength: minimax::Strength },
+    /// Ranked ballots, Baldwin's method.
+    Baldwin,
+    /// Ranked ballots, Nanson's method.
+    Nanson,
+    /// Ranked ballots, exact Kemeny-Young ranking.
+    KemenyYoung,
+    /// Approve of any number of options.
+    Approval,
+    /// Score every option from 0 to 5.
+    Score,
+    /// Score every option from 0 to 5, then an automatic runoff.
+    Star,
+    /// Grade every option from Reject to Great.
+    MajorityJudgment,
+    /// Quadratic voting under the session's `rules`.
+    Quadratic { rules: qv::Rules },
+}
+
+impl SessionMethod {
+    fn from_row(
+        method: &str,
+        qv_rules: Option<qv::Rules>,
+        tie_breaker: &str,
+        tiebreak_seed: i64,
+        seats: Option<i64>,
+        strength: Option<&str>,
+    ) -> Option<SessionMethod> {
+        match (method, qv_rules) {
+            // Sessions from before the strength could be chosen used winning
+            // votes.
+            ("cv", _) => Some(SessionMethod::Condorcet {
+                tie_breaker: tie_breaker_from_row(tie_breaker, tiebreak_seed)?,
+                strength: strength
+                    .map_or(Some(cv::Strength::WinningVotes), schulze_strength_from_row)?,
+            }),
+            ("rp", _) => Some(SessionMethod::RankedPairs {
+                tie_breaker: tie_breaker_from_row(tie_breaker, tiebreak_seed)?,
+            }),
+            ("queue", _) => Some(SessionMethod::ProportionalQueue {
+                tie_breaker: tie_breaker_from_row(tie_breaker, tiebreak_seed)?,
+            }),
+            ("irv", _) => Some(SessionMethod::InstantRunoff),
+            ("stv", _) => Some(SessionMethod::SingleTransferableVote {
+                seats: u32::try_from(seats?).ok()?,
+            }),
+            ("borda", _) => Some(SessionMethod::Borda),
+            ("copeland", _) => Some(SessionMethod::Copeland),
+            ("minimax", _) => Some(SessionMethod::Minimax {
+                strength: minimax_strength_from_row(strength?)?,
+            }),
+            ("baldwin", _) => Some(SessionMethod::Baldwin),
+            ("nanson", _) => Some(SessionMethod::Nanson),
+            ("kemeny", _) => Some(SessionMethod::KemenyYoung),
+            ("approval", _) => Some(SessionMethod::Approval),
+            ("score", _) => Some(SessionMethod::Score),
+            ("star", _) => Some(SessionMethod::Star),
+            ("mj", _) => Some(SessionMethod::MajorityJudgment),
+            ("qv", Some(rules)) => Some(SessionMethod::Quadratic { rules }),
+            (_, _) => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SessionMethod::Condorcet { .. } => "cv",
+            SessionMethod::RankedPairs { .. } => "rp",
+            SessionMethod::ProportionalQueue { .. } => "queue",
+            SessionMethod::InstantRunoff => "irv",
+            SessionMethod::SingleTransferableVote { .. } => "stv",
+            SessionMethod::Borda => "borda",
+            SessionMethod::Copeland => "copeland",
+            SessionMethod::Minimax { .. } => "minimax",
+            SessionMethod::Baldwin => "baldwin",
+            SessionMethod::Nanson => "nanson",
+            SessionMethod::KemenyYoung => "kemeny",
+            SessionMethod::Approval => "approval",
+            SessionMethod::Score => "score",
+            SessionMethod::Star => "star",
+            SessionMethod::MajorityJudgment => "mj",
+            SessionMethod::Quadratic { .. } => "qv",
+        }
+    }
+
+    // The `qv_tokens`, `qv_negative`, `qv_max_votes` and `qv_banking`
+    // columns.
+    fn qv_rules_row(&self) -> (Option<i64>, i64, Option<i64>, i64) {
+        match self {
+            SessionMethod::Quadratic { rules } => (
+                Some(rules.tokens_per_ballot),
+                i64::from(rules.negative_votes),
+                rules.max_votes,
+                i64::from(rules.banking),
+            ),
+            _ => (None, 1, None, 0),
+        }
+    }
+
+    fn seats(&self) -> Option<i64> {
+        match self {
+            SessionMethod::SingleTransferableVote { seats } => Some(i64::from(*seats)),
+            _ => None,
+        }
+    }
+
+    // The `strength` column.
+    fn strength_row(&self) -> Option<&'static str> {
+        match self {
+            SessionMethod::Minimax {
+                strength: minimax::Strength::WinningVotes,
+            } => Some("wv"),
+            SessionMethod::Minimax {
+                strength: minimax::Strength::Margins,
+            } => Some("margins"),
+            SessionMethod::Condorcet { strength, .. } => Some(match strength {
+                cv::Strength::WinningVotes => "wv",
+                cv::Strength::Margins => "margins",
+                cv::Strength::Ratio => "ratio",
+                cv::Strength::Combined => "combined",
+            }),
+            _ => None,
+        }
+    }
+
+    // The `tie_breaker` and `tiebreak_seed` columns.
+    fn tie_breaker_row(&self) -> (&'static str, i64) {
+        let tie_breaker = match self {
+            SessionMethod::Condorcet { tie_breaker, .. }
+            | SessionMethod::RankedPairs { tie_breaker }
+            | SessionMethod::ProportionalQueue { tie_breaker } => tie_breaker,
+            _ => return ("none", 0),
+        };
+        match tie_breaker {
+            cv::TieBreaker::None => ("none", 0),
+            cv::TieBreaker::RandomBallot { seed } => ("ballot", i64::try_from(*seed).unwrap()),
+            cv::TieBreaker::RandomOrder { seed } => ("random", i64::try_from(*seed).unwrap()),
+        }
+    }
+
+    fn ballot_example(&self) -> &'static str {
+        match self {
+            SessionMethod::Condorcet { .. }
+            | SessionMethod::RankedPairs { .. }
+            | SessionMethod::ProportionalQueue { .. }
+            | SessionMethod::SingleTransferableVote { .. }
+            | SessionMethod::Borda
+            | SessionMethod::Copeland
+            | SessionMethod::Minimax { .. }
+            | SessionMethod::Baldwin
+            | SessionMethod::Nanson
+            | SessionMethod::KemenyYoung => {
+                "Example response: `3 > 1 > 2 > 0`. Use `=` to rank options equally (`3 = 1 > 2`). You may rank only your top picks (`3 > 1`); unranked options count as tied last."
+            }
+            SessionMethod::InstantRunoff => {
+                "Example response: `3 > 1 > 2 > 0`. You may rank only your top picks (`3 > 1`)."
+            }
+            SessionMethod::Approval => {
+                "Example response: `1, 4, 5`, listing every option you approve of."
+            }
+            SessionMethod::Score => {
+                "Example response: `1: 5, 4: 3, 0: 1`. Score options from 0 to 5; options you leave out score 0."
+            }
+            SessionMethod::Star => {
+                "Example response: `1: 5, 4: 3, 0: 1`. Score options from 0 to 5; options you leave out score 0. The two highest totals go to an automatic runoff."
+            }
+            SessionMethod::MajorityJudgment => {
+                "Example response: `1: Great, 4: OK, 0: Poor`. Grades are Reject, Poor, OK, Good and Great; options you leave out are graded Reject."
+            }
+            SessionMethod::Quadratic { .. } => {
+                "Example response: `1: 3, 4: 6` (casting n votes for an option costs n*n tokens)"
+            }
+        }
+    }
+}
+
+fn tie_breaker_from_row(tie_breaker: &str, seed: i64) -> Option<cv::TieBreaker> {
+    let seed = u64::try_from(seed).ok()?;
+    match tie_breaker {
+        "none" => Some(cv::TieBreaker::None),
+        "ballot" => Some(cv::TieBreaker::RandomBallot { seed }),
+        "random" => Some(cv::TieBreaker::RandomOrder { seed }),
+        _ => None,
+    }
+}
+
+fn minimax_strength_from_row(strength: &str) -> Option<minimax::Strength> {
+    match strength {
+        "wv" => Some(minimax::Strength::WinningVotes),
+        "margins" => Some(minimax::Strength::Margins),
+        _ => None,
+    }
+}
+
+fn schulze_strength_from_row(strength: &str) -> Option<cv::Strength> {
+    match strength {
+        "wv" => Some(cv::Strength::WinningVotes),
+        "margins" => Some(cv::Strength::Margins),
+        "ratio" => Some(cv::Strength::Ratio),
+        "combined" => Some(cv::Strength::Combined),
+        _ => None,
+    }
+}
+
+// Quadratic rules from their columns, or `None` for sessions without a token
+// budget.
+fn qv_rules_from_row(
+    tokens: Option<i64>,
+    negative: i64,
+    max_votes: Option<i64>,
+    banking: i64,
+) -> Option<qv::Rules> {
+    return Some(qv::Rules {
+        tokens_per_ballot: tokens?,
+        negative_votes: negative != 0,
+        max_votes,
+        banking: banking != 0,
+    });
+}
+
+// A fresh tie-breaking seed for a new session. Kept below i64::MAX so it can
+// be stored in SQLite as is.
+fn new_tiebreak_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    return u64::try_from(nanos & u128::from(u64::MAX >> 1)).unwrap();
+}
+
+// Parses the optional tie-breaker argument of `cv`, `rp` and `queue` sessions.
+// `method` names the session method for errors about extra arguments.
+fn parse_tie_breaker(method: &str, args: &[&str], seed: u64) -> Result<cv::TieBreaker, ParseError> {
+    match args {
+        [] | ["ballot"] => Ok(cv::TieBreaker::RandomBallot { seed }),
+        ["random"] => Ok(cv::TieBreaker::RandomOrder { seed }),
+        ["none"] => Ok(cv::TieBreaker::None),
+        [tie_breaker] => Err(ParseError::UnknownTieBreaker(tie_breaker.to_string())),
+        [tie_breaker, extra, ..] => {
+            parse_tie_breaker(method, &[tie_breaker], seed)?;
+            Err(ParseError::UnexpectedArgument {
+                method: method.to_string(),
+                arg: extra.to_string(),
+            })
+        }
+    }
+}
+
+// Parses the optional tie-breaker and link strength arguments of `cv`
+// sessions, in either order, e.g. "margins" or "none ratio".
+fn parse_condorcet(args: &[&str], seed: u64) -> Result<SessionMethod, ParseError> {
+    let strengths: Vec<cv::Strength> = args
+        .iter()
+        .filter_map(|arg| schulze_strength_from_row(arg))
+        .collect();
+    let tie_breaker: Vec<&str> = args
+        .iter()
+        .filter(|arg| schulze_strength_from_row(arg).is_none())
+        .copied()
+        .collect();
+    let strength = match strengths.as_slice() {
+        [] => cv::Strength::WinningVotes,
+        [strength] => *strength,
+        _ => {
+            return Err(ParseError::ConflictingStrengths);
+        }
+    };
+    return Ok(SessionMethod::Condorcet {
+        tie_breaker: parse_tie_breaker("cv", &tie_breaker, seed)?,
+        strength,
+    });
+}
+
+// Parses the budget and optional rules of `qv` sessions, e.g. "100" or
+// "100 positive cap 5 bank".
+fn parse_qv_rules(tokens: &str, args: &[&str]) -> Result<qv::Rules, ParseError> {
+    let mut rules = match tokens.parse::<i64>() {
+        Ok(tokens) if tokens > 0 && tokens <= qv::MAX_TOKENS => qv::Rules::new(tokens),
+        _ => {
+            return Err(ParseError::InvalidTokens(tokens.to_string()));
+        }
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "positive" => rules.negative_votes = false,
+            "bank" => rules.banking = true,
+            "cap" => {
+                let cap = args.next().copied().unwrap_or_default();
+                match cap.parse::<i64>() {
+                    Ok(cap) if cap > 0 => rules.max_votes = Some(cap),
+                    _ => {
+                        return Err(ParseError::InvalidCap(cap.to_string()));
+                    }
+                }
+            }
+            rule => {
+                return Err(ParseError::UnknownQvRule(rule.to_string()));
+            }
+        }
+    }
+    return Ok(rules);
+}
+
+// Parses the arguments of `^newsession`, e.g. "" (Condorcet), "cv random",
+// "rp", "irv" or "qv 100". `seed` seeds the tie-breaker of ranked sessions.
+fn parse_session_method(args: &str, seed: u64) -> Result<SessionMethod, ParseError> {
+    let args: Vec<&str> = args.split_whitespace().collect();
+    match args.as_slice() {
+        [] => parse_condorcet(&[], seed),
+        ["cv", rest @ ..] => parse_condorcet(rest, seed),
+        ["rp", rest @ ..] => Ok(SessionMethod::RankedPairs {
+            tie_breaker: parse_tie_breaker("rp", rest, seed)?,
+        }),
+        ["queue", rest @ ..] => Ok(SessionMethod::ProportionalQueue {
+            tie_breaker: parse_tie_breaker("queue", rest, seed)?,
+        }),
+        ["irv"] => Ok(SessionMethod::InstantRunoff),
+        ["borda"] => Ok(SessionMethod::Borda),
+        ["copeland"] => Ok(SessionMethod::Copeland),
+        ["minimax"] | ["minimax", "wv"] => Ok(SessionMethod::Minimax {
+            strength: minimax::Strength::WinningVotes,
+        }),
+        ["minimax", "margins"] => Ok(SessionMethod::Minimax {
+            strength: minimax::Strength::Margins,
+        }),
+        ["minimax", "wv" | "margins", extra, ..] => Err(ParseError::UnexpectedArgument {
+            method: "minimax".to_string(),
+            arg: extra.to_string(),
+        }),
+        ["minimax", strength, ..] => Err(ParseError::UnknownDefeatStrength(strength.to_string())),
+        ["baldwin"] => Ok(SessionMethod::Baldwin),
+        ["nanson"] => Ok(SessionMethod::Nanson),
+        ["kemeny"] => Ok(SessionMethod::KemenyYoung),
+        ["approval"] => Ok(SessionMethod::Approval),
+        ["score"] => Ok(SessionMethod::Score),
+        ["star"] => Ok(SessionMethod::Star),
+        ["mj"] => Ok(SessionMethod::MajorityJudgment),
+        ["stv", seats] => match seats.parse::<u32>() {
+            Ok(seats) if seats > 0 => Ok(SessionMethod::SingleTransferableVote { seats }),
+            _ => Err(ParseError::InvalidSeats(seats.to_string())),
+        },
+        ["stv"] => Err(ParseError::MissingSeats),
+        ["qv", tokens, rest @ ..] => Ok(SessionMethod::Quadratic {
+            rules: parse_qv_rules(tokens, rest)?,
+        }),
+        ["qv"] => Err(ParseError::MissingTokens),
+        [
+            method @ ("irv" | "borda" | "copeland" | "baldwin" | "nanson" | "kemeny" | "approval"
+            | "score" | "star" | "mj"),
+            arg,
+            ..,
+        ]
+        | [method @ "stv", _, arg, ..] => Err(ParseError::UnexpectedArgument {
+            method: method.to_string(),
+            arg: arg.to_string(),
+        }),
+        [method, ..] => Err(ParseError::UnknownMethod(method.to_string())),
+    }
+}
+
+#[test]
+fn test_parse_session_method() {
+    struct Case {
+        input: &'static str,
+        expected: Option<SessionMethod>,
+    }
+
+    let cases = [
+        Case {
+            input: "",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::RandomBallot { seed: 9 },
+                strength: cv::Strength::WinningVotes,
+            }),
+        },
+        Case {
+            input: " cv ",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::RandomBallot { seed: 9 },
+                strength: cv::Strength::WinningVotes,
+            }),
+        },
+        Case {
+            input: "cv random",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::RandomOrder { seed: 9 },
+                strength: cv::Strength::WinningVotes,
+            }),
+        },
+        Case {
+            input: "cv none",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::None,
+                strength: cv::Strength::WinningVotes,
+            }),
+        },
+        Case {
+            input: "cv coin",
+            expected: None,
+        },
+        Case {
+            input: "cv margins",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::RandomBallot { seed: 9 },
+                strength: cv::Strength::Margins,
+            }),
+        },
+        Case {
+            input: "cv ratio none",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::None,
+                strength: cv::Strength::Ratio,
+            }),
+        },
+        Case {
+            input: "cv random combined",
+            expected: Some(SessionMethod::Condorcet {
+                tie_breaker: cv::TieBreaker::RandomOrder { seed: 9 },
+                strength: cv::Strength::Combined,
+            }),
+        },
+        Case {
+            input: "cv wv margins",
+            expected: None,
+        },
+        Case {
+            input: "rp",
+            expected: Some(SessionMethod::RankedPairs {
+                tie_breaker: cv::TieBreaker::RandomBallot { seed: 9 },
+            }),
+        },
+        Case {
+            input: "rp none",
+            expected: Some(SessionMethod::RankedPairs {
+                tie_breaker: cv::TieBreaker::None,
+            }),
+        },
+        Case {
+            input: "irv",
+            expected: Some(SessionMethod::InstantRunoff),
+        },
+        Case {
+            input: "queue random",
+            expected: Some(SessionMethod::ProportionalQueue {
+                tie_breaker: cv::TieBreaker::RandomOrder { seed: 9 },
+            }),
+        },
+        Case {
+            input: "stv 3",
+            expected: Some(SessionMethod::SingleTransferableVote { seats: 3 }),
+        },
+        Case {
+            input: "stv 0",
+            expected: None,
+        },
+        Case {
+            input: "borda",
+            expected: Some(SessionMethod::Borda),
+        },
+        Case {
+            input: "copeland",
+            expected: Some(SessionMethod::Copeland),
+        },
+        Case {
+            input: "minimax",
+            expected: Some(SessionMethod::Minimax {
+                strength: minimax::Strength::WinningVotes,
+            }),
+        },
+        Case {
+            input: "minimax margins",
+            expected: Some(SessionMethod::Minimax {
+                strength: minimax::Strength::Margins,
+            }),
+        },
+        Case {
+            input: "minimax ratio",
+            expected: None,
+        },
+        Case {
+            input: "baldwin",
+            expected: Some(SessionMethod::Baldwin),
+        },
+        Case {
+            input: "nanson",
+            expected: Some(SessionMethod::Nanson),
+        },
+        Case {
+            input: "kemeny",
+            expected: Some(SessionMethod::KemenyYoung),
+        },
+        Case {
+            input: "approval",
+            expected: Some(SessionMethod::Approval),
+        },
+        Case {
+            input: "score",
+            expected: Some(SessionMethod::Score),
+        },
+        Case {
+            input: "star",
+            expected: Some(SessionMethod::Star),
+        },
+        Case {
+            input: "mj",
+            expected: Some(SessionMethod::MajorityJudgment),
+        },
+        Case {
+            input: "qv 100",
+            expected: Some(SessionMethod::Quadratic {
+                rules: qv::Rules::new(100),
+            }),
+        },
+        Case {
+            input: "qv 100 positive cap 5 bank",
+            expected: Some(SessionMethod::Quadratic {
+                rules: qv::Rules {
+                    tokens_per_ballot: 100,
+                    negative_votes: false,
+                    max_votes: Some(5),
+                    banking: true,
+                },
+            }),
+        },
+        Case {
+            input: "qv 100 cap",
+            expected: None,
+        },
+        Case {
+            input: "qv 100 cap 0",
+            expected: None,
+        },
+        Case {
+            input: "qv 100 negative",
+            expected: None,
+        },
+        Case {
+            input: "qv",
+            expected: None,
+        },
+        Case {
+            input: "qv -4",
+            expected: None,
+        },
+        Case {
+            input: "qv 9223372036854775807",
+            expected: None,
+        },
+        Case {
+            input: "dictator",
+            expected: None,
+        },
+        Case {
+            input: "irv fast",
+            expected: None,
+        },
+    ];
+
+    for case in cases.iter() {
+        assert_eq!(
+            parse_session_method(case.input, 9).ok(),
+            case.expected,
+            "{}",
+            case.input
+        );
+    }
+
+    assert_eq!(
+        parse_session_method("irv fast", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "irv".to_string(),
+            arg: "fast".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("stv 3 4", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "stv".to_string(),
+            arg: "4".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("score 0-10", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "score".to_string(),
+            arg: "0-10".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("mj grades", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "mj".to_string(),
+            arg: "grades".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("borda dowdall", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "borda".to_string(),
+            arg: "dowdall".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("kemeny fast", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "kemeny".to_string(),
+            arg: "fast".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("rp ballot random", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "rp".to_string(),
+            arg: "random".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("cv none margins random", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "cv".to_string(),
+            arg: "random".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("queue coin random", 9),
+        Err(ParseError::UnknownTieBreaker("coin".to_string()))
+    );
+    assert_eq!(
+        parse_session_method("minimax margins extra", 9),
+        Err(ParseError::UnexpectedArgument {
+            method: "minimax".to_string(),
+            arg: "extra".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_session_method("dictator", 9),
+        Err(ParseError::UnknownMethod("dictator".to_string()))
+    );
+
+    // Every method survives a round trip through its stored columns.
+    for case in cases.iter() {
+        if let Some(method) = &case.expected {
+            let (tie_breaker, seed) = method.tie_breaker_row();
+            let (tokens, negative, max_votes, banking) = method.qv_rules_row();
+            assert_eq!(
+                SessionMethod::from_row(
+                    method.name(),
+                    qv_rules_from_row(tokens, negative, max_votes, banking),
+                    tie_breaker,
+                    seed,
+                    method.seats(),
+                    method.strength_row()
+                ),
+                Some(method.clone())
+            );
+        }
+    }
+}
+
+// A single stored row of a ballot: the option and either its rank (ranked
+// methods) or the number of votes cast for it (quadratic voting).
+#[derive(Debug, PartialEq)]
+struct BallotEntry {
+    option_id: u32,
+    rank: i64,
+    votes: i64,
+}
+
+// One stored row of `voter`'s ballot, as `ballot_entries` returns them.
+#[cfg(test)]
+fn ballot_entry(voter: &str, option_id: u32, rank: i64, votes: i64) -> (String, BallotEntry) {
+    return (
+        voter.to_string(),
+        BallotEntry {
+            option_id,
+            rank,
+            votes,
+        },
+    );
+}
+
+// Groups stored entries into one ranked ballot per voter. Entries sharing a
+// rank form one tier of equally ranked options.
+fn ranked_ballots(entries: &[(String, BallotEntry)]) -> Vec<cv::Ballot> {
+    return ranked_ballots_by_voter(entries).into_values().collect();
+}
+
+fn ranked_ballots_by_voter(entries: &[(String, BallotEntry)]) -> BTreeMap<&String, cv::Ballot> {
+    let tiers_by_user: BTreeMap<&String, BTreeMap<i64, Vec<u32>>> = entries.iter().fold(
+        BTreeMap::new(),
+        |mut map: BTreeMap<&String, BTreeMap<i64, Vec<u32>>>, (voter, entry)| {
+            map.entry(voter)
+                .or_default()
+                .entry(entry.rank)
+                .or_default()
+                .push(entry.option_id);
+            return map;
+        },
+    );
+    return tiers_by_user
+        .into_iter()
+        .map(|(voter, tiers)| (voter, tiers.into_values().collect()))
+        .collect();
+}
+
+// `ranked_ballots`, each counted with its voter's weight. Voters without
+// one count once.
+fn weighted_ranked_ballots(
+    entries: &[(String, BallotEntry)],
+    weights: &BTreeMap<String, cv::Weight>,
+) -> Vec<cv::WeightedBallot> {
+    return ranked_ballots_by_voter(entries)
+        .into_iter()
+        .map(|(voter, ballot)| cv::WeightedBallot {
+            weight: weights.get(voter).copied().unwrap_or(cv::Weight::from(1)),
+            ballot,
+        })
+        .collect();
+}
+
+#[test]
+fn test_weighted_ranked_ballots() {
+    let entries = vec![
+        ballot_entry("alice", 1, 0, 0),
+        ballot_entry("bob", 0, 0, 0),
+        ballot_entry("bob", 1, 1, 0),
+    ];
+    let weights = BTreeMap::from([("alice".to_string(), cv::Weight::new(1, 2))]);
+    assert_eq!(
+        weighted_ranked_ballots(&entries, &weights),
+        vec![
+            cv::WeightedBallot {
+                weight: cv::Weight::new(1, 2),
+                ballot: vec![vec![1]],
+            },
+            cv::WeightedBallot {
+                weight: cv::Weight::from(1),
+                ballot: vec![vec![0], vec![1]],
+            },
+        ]
+    );
+}
+
+/// The largest numerator or denominator of a ballot weight.
+const MAX_WEIGHT_TERM: u32 = 1000;
+
+// Parses a ballot weight such as "2" or "1/2".
+fn parse_weight(weight: &str) -> Result<cv::Weight, ParseError> {
+    let invalid = || ParseError::InvalidWeight(weight.to_string());
+    let (numer, denom) = match weight.split_once('/') {
+        Some((numer, denom)) => (numer.trim(), denom.trim()),
+        None => (weight.trim(), "1"),
+    };
+    let numer = numer.parse::<u32>().map_err(|_| invalid())?;
+    let denom = denom.parse::<u32>().map_err(|_| invalid())?;
+    if numer == 0 || denom == 0 || numer > MAX_WEIGHT_TERM || denom > MAX_WEIGHT_TERM {
+        return Err(invalid());
+    }
+    return Ok(cv::Weight::new(numer, denom));
+}
+
+#[test]
+fn test_parse_weight() {
+    assert_eq!(parse_weight("2").unwrap(), cv::Weight::from(2));
+    assert_eq!(parse_weight("1/2").unwrap(), cv::Weight::new(1, 2));
+    assert_eq!(parse_weight("2/4").unwrap(), cv::Weight::new(1, 2));
+    assert_eq!(parse_weight(" 3 / 2 ").unwrap(), cv::Weight::new(3, 2));
+    for bad in ["0", "1/0", "-1", "0.5", "half", "1/2/3", "1001", ""] {
+        assert!(parse_weight(bad).is_err(), "{bad}");
+    }
+}
+
+// Whether a member can manage the server in `guild_id`: its owner always can,
+// as can anyone with a role granting Manage Server or Administrator. The
+// @everyone role shares the server's ID and applies to every member.
+fn can_manage_guild(
+    guild_id: GuildId,
+    is_owner: bool,
+    role_permissions: &BTreeMap<RoleId, Permissions>,
+    member_roles: &[RoleId],
+) -> bool {
+    if is_owner {
+        return true;
+    }
+    return role_permissions.iter().any(|(role, permissions)| {
+        (role.get() == guild_id.get() || member_roles.contains(role))
+            && (permissions.manage_guild() || permissions.administrator())
+    });
+}
+
+#[test]
+fn test_can_manage_guild() {
+    let guild_id = GuildId::new(1);
+    let (admin, moderator, member) = (RoleId::new(2), RoleId::new(3), RoleId::new(4));
+    let roles = BTreeMap::from([
+        (RoleId::new(1), Permissions::SEND_MESSAGES),
+        (admin, Permissions::ADMINISTRATOR),
+        (moderator, Permissions::MANAGE_GUILD),
+        (member, Permissions::READ_MESSAGE_HISTORY),
+    ]);
+    assert!(can_manage_guild(guild_id, true, &roles, &[]));
+    assert!(can_manage_guild(guild_id, false, &roles, &[member, admin]));
+    assert!(can_manage_guild(guild_id, false, &roles, &[moderator]));
+    assert!(!can_manage_guild(guild_id, false, &roles, &[member]));
+    assert!(!can_manage_guild(guild_id, false, &roles, &[]));
+
+    // Everyone can manage a server whose @everyone role allows it.
+    let roles = BTreeMap::from([(RoleId::new(1), Permissions::MANAGE_GUILD)]);
+    assert!(can_manage_guild(guild_id, false, &roles, &[]));
+}
+
+#[test]
+fn test_ranked_ballots() {
+    let entries = vec![
+        ballot_entry("alice", 2, 0, 0),
+        ballot_entry("alice", 0, 1, 0),
+        ballot_entry("alice", 1, 1, 0),
+        ballot_entry("bob", 1, 0, 0),
+    ];
+    assert_eq!(
+        ranked_ballots(&entries),
+        vec![
+            cv::Ballot::from([vec![2], vec![0, 1]]),
+            cv::Ballot::from([vec![1]]),
+        ],
+    );
+}
+
+// Groups stored entries into one quadratic ballot per voter, each with the
+// credits its voter banked. Voters without credits have none.
+fn qv_ballots(
+    entries: &[(String, BallotEntry)],
+    credits: &BTreeMap<String, i64>,
+) -> Vec<qv::BankedBallot> {
+    return qv_ballots_by_voter(entries, credits)
+        .into_values()
+        .collect();
+}
+
+fn qv_ballots_by_voter<'a>(
+    entries: &'a [(String, BallotEntry)],
+    credits: &BTreeMap<String, i64>,
+) -> BTreeMap<&'a String, qv::BankedBallot> {
+    return entries.iter().fold(
+        BTreeMap::new(),
+        |mut map: BTreeMap<&String, qv::BankedBallot>, (voter, entry)| {
+            map.entry(voter)
+                .or_insert_with(|| qv::BankedBallot {
+                    credits: credits.get(voter).copied().unwrap_or(0),
+                    ballot: qv::Ballot::new(),
+                })
+                .ballot
+                .insert(i64::from(entry.option_id), entry.votes);
+            return map;
+        },
+    );
+}
+
+// The credits each participant of a quadratic session banks: what they left
+// unspent of their budget and `credits`, as `rules` allow. Participants
+// without stored entries spent nothing.
+fn banked_credits(
+    rules: &qv::Rules,
+    entries: &[(String, BallotEntry)],
+    credits: &BTreeMap<String, i64>,
+) -> BTreeMap<String, i64> {
+    let ballots = qv_ballots_by_voter(entries, credits);
+    return credits
+        .iter()
+        .map(|(member, member_credits)| {
+            let left = match ballots.get(member) {
+                Some(ballot) => qv::tokens_left(ballot, rules),
+                None => qv::tokens_left(
+                    &qv::BankedBallot {
+                        credits: *member_credits,
+                        ballot: qv::Ballot::new(),
+                    },
+                    rules,
+                ),
+            };
+            return (member.clone(), rules.banked(left));
+        })
+        .collect();
+}
+
+#[test]
+fn test_qv_credits() {
+    let entries = vec![
+        ballot_entry("alice", 0, 0, 3),
+        ballot_entry("alice", 1, 0, -1),
+        ballot_entry("bob", 1, 0, 2),
+    ];
+    let credits = BTreeMap::from([
+        ("alice".to_string(), 4),
+        ("bob".to_string(), 0),
+        ("carol".to_string(), 2),
+    ]);
+    assert_eq!(
+        qv_ballots(&entries, &credits),
+        vec![
+            qv::BankedBallot {
+                credits: 4,
+                ballot: qv::Ballot::from([(0, 3), (1, -1)]),
+            },
+            qv::BankedBallot {
+                credits: 0,
+                ballot: qv::Ballot::from([(1, 2)]),
+            },
+        ]
+    );
+
+    let rules = qv::Rules {
+        banking: true,
+        ..qv::Rules::new(10)
+    };
+    // Alice spends 10 of 14, Bob 4 of 10 and Carol nothing of 12, which is
+    // capped at one budget.
+    assert_eq!(
+        banked_credits(&rules, &entries, &credits),
+        BTreeMap::from([
+            ("alice".to_string(), 4),
+            ("bob".to_string(), 6),
+            ("carol".to_string(), 10),
+        ])
+    );
+    assert!(
+        banked_credits(&qv::Rules::new(10), &entries, &credits)
+            .values()
+            .all(|banked| *banked == 0)
+    );
+}
+
+// One rating per option per voter: the stored votes of approval, score,
+// STAR, Majority Judgment and quadratic ballots.
+fn rated_ballots(entries: &[(String, BallotEntry)]) -> Vec<BTreeMap<u32, i64>> {
+    let ballots_by_user: BTreeMap<&String, BTreeMap<u32, i64>> = entries.iter().fold(
+        BTreeMap::new(),
+        |mut map: BTreeMap<&String, BTreeMap<u32, i64>>, (voter, entry)| {
+            map.entry(voter)
+                .or_default()
+                .insert(entry.option_id, entry.votes);
+            return map;
+        },
+    );
+    return ballots_by_user.into_values().collect();
+}
+
+// Runs every method that can count a session's stored ballots. Rated
+// ballots get their own method first, then every ranked method on the
+// rankings they imply.
+fn comparison_rows(
+    method: &SessionMethod,
+    num_choices: u32,
+    entries: &[(String, BallotEntry)],
+    weights: &BTreeMap<String, cv::Weight>,
+    qv_credits: &BTreeMap<String, i64>,
+) -> Vec<compare::Row> {
+    let mut rows = match method {
+        SessionMethod::Approval => vec![compare::row(
+            &av::ApprovalVoting,
+            num_choices,
+            approval_ballots(entries),
+        )],
+        SessionMethod::Score | SessionMethod::Star => vec![
+            compare::row(&sv::ScoreVoting, num_choices, score_ballots(entries)),
+            compare::row(&star::StarVoting, num_choices, score_ballots(entries)),
+        ],
+        SessionMethod::MajorityJudgment => vec![compare::row(
+            &mj::MajorityJudgment,
+            num_choices,
+            mj_ballots(entries),
+        )],
+        SessionMethod::Quadratic { rules } => vec![compare::row(
+            &qv::QuadraticVoting { rules: *rules },
+            num_choices,
+            qv_ballots(entries, qv_credits),
+        )],
+        _ => {
+            return compare::weighted_ranked_rows(
+                num_choices,
+                &weighted_ranked_ballots(entries, weights),
+            );
+        }
+    };
+
+    let ranked: Vec<cv::Ballot> = rated_ballots(entries)
+        .iter()
+        .map(|ratings| compare::ranked_from_ratings(num_choices, ratings))
+        .collect();
+    rows.extend(compare::ranked_rows(num_choices, &ranked));
+    return rows;
+}
+
+#[test]
+fn test_comparison_rows() {
+    // Ranked ballots run every ranked method.
+    let entries = vec![
+        ballot_entry("a", 0, 0, 0),
+        ballot_entry("a", 1, 1, 0),
+        ballot_entry("b", 1, 0, 0),
+        ballot_entry("b", 0, 1, 0),
+        ballot_entry("c", 0, 0, 0),
+    ];
+    let method = SessionMethod::Condorcet {
+        tie_breaker: cv::TieBreaker::None,
+        strength: cv::Strength::WinningVotes,
+    };
+    let rows = comparison_rows(&method, 2, &entries, &BTreeMap::new(), &BTreeMap::new());
+    assert_eq!(rows.len(), compare::ranked_rows(2, &[]).len());
+    assert!(
+        rows.iter()
+            .all(|row| row.result.as_ref().unwrap().0 == vec![0])
+    );
+
+    // Scores are compared natively and as rankings, where the tie on
+    // voter b's ballot keeps instant-runoff out.
+    let entries = vec![
+        ballot_entry("a", 0, 0, 5),
+        ballot_entry("a", 1, 0, 1),
+        ballot_entry("b", 0, 0, 2),
+        ballot_entry("b", 1, 0, 2),
+    ];
+    let rows = comparison_rows(
+        &SessionMethod::Star,
+        2,
+        &entries,
+        &BTreeMap::new(),
+        &BTreeMap::new(),
+    );
+    assert_eq!(rows[0].method, "Score");
+    assert_eq!(rows[1].method, "STAR");
+    assert_eq!(rows[0].result.as_ref().unwrap().0, vec![0]);
+    assert!(
+        rows.iter()
+            .any(|row| row.method == "Instant-runoff" && row.result.is_err())
+    );
+}
+
+// One approval ballot per voter: the options they gave a vote.
+fn approval_ballots(entries: &[(String, BallotEntry)]) -> Vec<av::Ballot> {
+    let ballots_by_user: BTreeMap<&String, av::Ballot> = entries.iter().fold(
+        BTreeMap::new(),
+        |mut map: BTreeMap<&String, av::Ballot>, (voter, entry)| {
+            let ballot = map.entry(voter).or_default();
+            if entry.votes > 0 {
+                ballot.insert(entry.option_id);
+            }
+            return map;
+        },
+    );
+    return ballots_by_user.into_values().collect();
+}
+
+// One score ballot per voter, scores stored as votes.
+fn score_ballots(entries: &[(String, BallotEntry)]) -> Vec<sv::Ballot> {
+    let ballots_by_user: BTreeMap<&String, sv::Ballot> = entries.iter().fold(
+        BTreeMap::new(),
+        |mut map: BTreeMap<&String, sv::Ballot>, (voter, entry)| {
+            // Scores were checked when the ballot was cast; a negative one
+            // can only come from a tampered database and counts as 0.
+            map.entry(voter)
+                .or_default()
+                .insert(entry.option_id, u32::try_from(entry.votes).unwrap_or(0));
+            return map;
+        },
+    );
+    return ballots_by_user.into_values().collect();
+}
+
+// One Majority Judgment ballot per voter, grades stored as votes.
+fn mj_ballots(entries: &[(String, BallotEntry)]) -> Vec<mj::Ballot> {
+    let ballots_by_user: BTreeMap<&String, mj::Ballot> = entries.iter().fold(
+        BTreeMap::new(),
+        |mut map: BTreeMap<&String, mj::Ballot>, (voter, entry)| {
+            // Grades were checked when the ballot was cast; an unknown one
+            // can only come from a tampered database and counts as Reject.
+            map.entry(voter).or_default().insert(
+                entry.option_id,
+                mj::Grade::try_from(entry.votes).unwrap_or(mj::Grade::Reject),
+            );
+            return map;
+        },
+    );
+    return ballots_by_user.into_values().collect();
+}
+
+// Parses and validates a DM'd ballot for the session's method.
+fn parse_ballot_entries(
+    method: &SessionMethod,
+    ballot_str: String,
+    option_id_to_option: &BTreeMap<u32, String>,
+) -> Result<Vec<BallotEntry>, ParseError> {
+    match method {
+        SessionMethod::Condorcet { .. }
+        | SessionMethod::RankedPairs { .. }
+        | SessionMethod::ProportionalQueue { .. }
+        | SessionMethod::SingleTransferableVote { .. }
+        | SessionMethod::Borda
+        | SessionMethod::Copeland
+        | SessionMethod::Minimax { .. }
+        | SessionMethod::Baldwin
+        | SessionMethod::Nanson
+        | SessionMethod::KemenyYoung
+        | SessionMethod::InstantRunoff => {
+            // Ballots may rank only some of the options; unranked options
+            // count as tied below every ranked one.
+            let ballot = parse_cv_ballot(ballot_str)?;
+
+            // TODO: parse, don't validate
+            for ballot_entry in ballot.iter().flatten() {
+                if !option_id_to_option.contains_key(ballot_entry) {
+                    return Err(ParseError::UnknownOption(i64::from(*ballot_entry)));
+                }
+            }
+
+            if *method == SessionMethod::InstantRunoff && ballot.iter().any(|tier| tier.len() > 1) {
+                return Err(ParseError::EqualRanks);
+            }
+
+            return Ok(ballot
+                .into_iter()
+                .enumerate()
+                .flat_map(|(rank, tier)| {
+                    tier.into_iter().map(move |option_id| BallotEntry {
+                        option_id,
+                        rank: i64::try_from(rank).unwrap(),
+                        votes: 0,
+                    })
+                })
+                .collect());
+        }
+        SessionMethod::Approval => {
+            let ballot = parse_approval_ballot(ballot_str)?;
+            let mut entries = Vec::new();
+            for option_id in ballot {
+                if !option_id_to_option.contains_key(&option_id) {
+                    return Err(ParseError::UnknownOption(i64::from(option_id)));
+                }
+                entries.push(BallotEntry {
+                    option_id,
+                    rank: 0,
+                    votes: 1,
+                });
+            }
+            return Ok(entries);
+        }
+        SessionMethod::Score | SessionMethod::Star => {
+            let ballot = parse_score_ballot(ballot_str)?;
+            let mut entries = Vec::new();
+            for (option_id, score) in ballot {
+                if !option_id_to_option.contains_key(&option_id) {
+                    return Err(ParseError::UnknownOption(i64::from(option_id)));
+                }
+                entries.push(BallotEntry {
+                    option_id,
+                    rank: 0,
+                    votes: i64::from(score),
+                });
+            }
+            return Ok(entries);
+        }
+        SessionMethod::MajorityJudgment => {
+            let ballot = parse_mj_ballot(ballot_str)?;
+            let mut entries = Vec::new();
+            for (option_id, grade) in ballot {
+                if !option_id_to_option.contains_key(&option_id) {
+                    return Err(ParseError::UnknownOption(i64::from(option_id)));
+                }
+                entries.push(BallotEntry {
+                    option_id,
+                    rank: 0,
+                    votes: i64::from(grade),
+                });
+            }
+            return Ok(entries);
+        }
+        SessionMethod::Quadratic { rules } => {
+            let ballot = parse_qv_ballot(ballot_str)?;
+            let num_choices = u32::try_from(option_id_to_option.len()).unwrap();
+            qv::check_ballot(&ballot, rules, num_choices).map_err(ParseError::InvalidQvBallot)?;
+
+            let mut entries = Vec::new();
+            for (choice, votes) in ballot.iter() {
+                match u32::try_from(*choice) {
+                    Ok(option_id) if option_id_to_option.contains_key(&option_id) => {
+                        entries.push(BallotEntry {
+                            option_id,
+                            rank: 0,
+                            votes: *votes,
+                        });
+                    }
+                    _ => {
+                        return Err(ParseError::UnknownOption(*choice));
+                    }
+                }
+            }
+
+            return Ok(entries);
+        }
+    }
+}
+
+#[test]
+fn test_parse_ballot_entries() {
+    let options = BTreeMap::from([
+        (0, "A".to_string()),
+        (1, "B".to_string()),
+        (2, "C".to_string()),
+    ]);
+    let cv_method = SessionMethod::Condorcet {
+        tie_breaker: cv::TieBreaker::None,
+        strength: cv::Strength::WinningVotes,
+    };
+
+    assert_eq!(
+        parse_ballot_entries(&cv_method, "2 > 0 > 1".to_string(), &options).unwrap(),
+        vec![
+            BallotEntry {
+                option_id: 2,
+                rank: 0,
+                votes: 0
+            },
+            BallotEntry {
+                option_id: 0,
+                rank: 1,
+                votes: 0
+            },
+            BallotEntry {
+                option_id: 1,
+                rank: 2,
+                votes: 0
+            },
+        ],
+    );
+    assert_eq!(
+        parse_ballot_entries(&cv_method, "2".to_string(), &options).unwrap(),
+        vec![BallotEntry {
+            option_id: 2,
+            rank: 0,
+            votes: 0
+        }],
+    );
+    assert_eq!(
+        parse_ballot_entries(&cv_method, "2 > 5".to_string(), &options),
+        Err(ParseError::UnknownOption(5))
+    );
+    assert_eq!(
+        parse_ballot_entries(&cv_method, "2 = 0 > 1".to_string(), &options).unwrap(),
+        vec![
+            BallotEntry {
+                option_id: 2,
+                rank: 0,
+                votes: 0
+            },
+            BallotEntry {
+                option_id: 0,
+                rank: 0,
+                votes: 0
+            },
+            BallotEntry {
+                option_id: 1,
+                rank: 1,
+                votes: 0
+            },
+        ],
+    );
+    assert_eq!(
+        parse_ballot_entries(
+            &SessionMethod::InstantRunoff,
+            "2 = 0 > 1".to_string(),
+            &options
+        ),
+        Err(ParseError::EqualRanks)
+    );
+
+    let qv_method = SessionMethod::Quadratic {
+        rules: qv::Rules::new(10),
+    };
+    assert_eq!(
+        parse_ballot_entries(&qv_method, "2: 3, 0: -1".to_string(), &options).unwrap(),
+        vec![
+            BallotEntry {
+                option_id: 0,
+                rank: 0,
+                votes: -1
+            },
+            BallotEntry {
+                option_id: 2,
+                rank: 0,
+                votes: 3
+            },
+        ],
+    );
+    // 3*3 + 2*2 = 13 tokens
+    assert_eq!(
+        parse_ballot_entries(&qv_method, "2: 3, 0: 2".to_string(), &options),
+        Err(ParseError::InvalidQvBallot(qv::BallotError::OverBudget {
+            tokens: Some(13),
+            budget: 10
+        }))
+    );
+    assert_eq!(
+        parse_ballot_entries(&qv_method, "3: 1".to_string(), &options),
+        Err(ParseError::InvalidQvBallot(qv::BallotError::UnknownOption(
+            3
+        )))
+    );
+    assert_eq!(
+        parse_ballot_entries(&qv_method, "-1: 1".to_string(), &options),
+        Err(ParseError::InvalidQvBallot(
+            qv::BallotError::NegativeOption(-1)
+        ))
+    );
+    let positive_method = SessionMethod::Quadratic {
+        rules: qv::Rules {
+            negative_votes: false,
+            max_votes: Some(2),
+            ..qv::Rules::new(10)
+        },
+    };
+    assert_eq!(
+        parse_ballot_entries(&positive_method, "2: 1, 0: -1".to_string(), &options),
+        Err(ParseError::InvalidQvBallot(
+            qv::BallotError::NegativeVotes {
+                choice: 0,
+                votes: -1
+            }
+        ))
+    );
+    assert_eq!(
+        parse_ballot_entries(&positive_method, "2: 3".to_string(), &options),
+        Err(ParseError::InvalidQvBallot(qv::BallotError::OverCap {
+            choice: 2,
+            votes: 3,
+            max_votes: 2
+        }))
+    );
 }
 
 #[async_trait]
@@ -381,14 +2455,12 @@ impl EventHandler for Bot {
                     msg.channel_id
                 );
             }
-            ()
         };
         let dm_respond = async |to_send: &str| -> () {
             let msg_to_send = CreateMessage::new().content(to_send);
             if let Err(say_err) = msg.author.direct_message(&ctx, msg_to_send).await {
                 error!("Failed to respond to author {}: {say_err}", msg.author);
             }
-            ()
         };
         let participate = async |tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
                                  session_id: i64,
@@ -406,12 +2478,12 @@ impl EventHandler for Bot {
             {
                 Ok(_) => {}
                 Err(err) => {
-                    error!("Failed to insert into session_participants. session_id: {session_id}. participant: {user}. Err: {err}");
+                    error!(
+                        "Failed to insert into session_participants. session_id: {session_id}. participant: {user}. Err: {err}"
+                    );
                     chan_respond("Failed to add you to the participants of the voting session.")
                         .await;
-                    return Err(ParticipateError {
-                        details: format!("{err}"),
-                    });
+                    return Err(ParticipateError::Database(err));
                 }
             };
 
@@ -427,7 +2499,9 @@ impl EventHandler for Bot {
         /////////////////////////////////////////////////////////////
         // ^newsession
         /////////////////////////////////////////////////////////////
-        if msg.content.eq("^newsession") {
+        if let Some(method_args) = msg.content.strip_prefix("^newsession")
+            && (method_args.is_empty() || method_args.starts_with(char::is_whitespace))
+        {
             let guild = match msg.guild_id {
                 Some(id) => id.to_string(),
                 None => {
@@ -437,9 +2511,22 @@ impl EventHandler for Bot {
             };
             let channel = msg.channel_id.to_string();
 
+            let method = match parse_session_method(method_args, new_tiebreak_seed()) {
+                Ok(method) => method,
+                Err(err) => {
+                    chan_respond(&format!("Failed to make a new session: {err}")).await;
+                    return;
+                }
+            };
+            let method_name = method.name();
+            let (qv_tokens, qv_negative, qv_max_votes, qv_banking) = method.qv_rules_row();
+            let (tie_breaker, tiebreak_seed) = method.tie_breaker_row();
+            let seats = method.seats();
+            let strength = method.strength_row();
+
             let mut newsession_tx = self.database.begin().await.unwrap();
             match sqlx::query!(
-                "UPDATE voting_session SET status = 'closed_new' WHERE discord_server = ? AND server_channel = ?",
+                "UPDATE voting_session SET status = 'closed_new' WHERE discord_server = ? AND server_channel = ? AND status <> 'finished'",
                 guild, channel,
             ).execute(&mut *newsession_tx).await {
                 Ok(_) => {}
@@ -452,15 +2539,24 @@ impl EventHandler for Bot {
             };
 
             match sqlx::query!(
-                "INSERT INTO voting_session (discord_server, server_channel, status) VALUES (?,?, 'open')",
+                "INSERT INTO voting_session (discord_server, server_channel, status, method, qv_tokens, qv_negative, qv_max_votes, qv_banking, tie_breaker, tiebreak_seed, seats, strength) VALUES (?,?, 'open', ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 guild,
                 channel,
+                method_name,
+                qv_tokens,
+                qv_negative,
+                qv_max_votes,
+                qv_banking,
+                tie_breaker,
+                tiebreak_seed,
+                seats,
+                strength,
             )
             .execute(&mut *newsession_tx)
             .await
             {
                 Ok(_) => {
-                    info!("Started voting_session. Server: {guild}. Channel: {channel}");
+                    info!("Started voting_session. Server: {guild}. Channel: {channel}. Method: {method:?}");
                 }
                 Err(err) => {
                     error!("Failed to insert voting_session: {err}");
@@ -539,7 +2635,7 @@ impl EventHandler for Bot {
                 }
             };
 
-            if suggestion.trim().len() == 0 {
+            if suggestion.trim().is_empty() {
                 chan_respond("Cannot vote with an empty string").await;
                 return;
             }
@@ -577,7 +2673,9 @@ impl EventHandler for Bot {
                 .is_participating(&mut option_insert_tx, latest_guild_session, user.clone())
                 .await
             {
-                debug!("User {user} is not yet participating in session {latest_guild_session} for guild {guild}. Adding.");
+                debug!(
+                    "User {user} is not yet participating in session {latest_guild_session} for guild {guild}. Adding."
+                );
                 if let Err(err) =
                     participate(&mut option_insert_tx, latest_guild_session, &user).await
                 {
@@ -731,17 +2829,68 @@ SELECT
                 }
             };
 
-            let mut ballot_message = format!("Ballot options:\n");
-            for (id, option) in self
+            let method = match self.session_method(&mut tx, latest_guild_session).await {
+                Some(method) => method,
+                None => {
+                    chan_respond("Failed to start vote").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+
+            let options = self
                 .option_id_to_option(&mut tx, latest_guild_session)
-                .await
+                .await;
+            if let SessionMethod::SingleTransferableVote { seats } = method
+                && vm::index(seats) > options.len()
             {
+                info!(
+                    "Refused to start voting for session {latest_guild_session}: {seats} seats, {} options",
+                    options.len()
+                );
+                chan_respond(&format!(
+                    "Cannot fill {seats} seats with {} options. Suggest more options with `^suggest` first.",
+                    options.len()
+                ))
+                .await;
+                tx.rollback().await.unwrap();
+                return;
+            }
+
+            let mut ballot_message = "Ballot options:\n".to_string();
+            for (id, option) in options {
                 ballot_message += &format!("{}: {}\n", id, option);
             }
-            ballot_message += "\nExample response: `3 > 1 > 2 > 0`";
+            if let SessionMethod::Quadratic { rules } = method {
+                ballot_message +=
+                    &format!("\nYou have {} tokens to spend.", rules.tokens_per_ballot);
+                if !rules.negative_votes {
+                    ballot_message += " Negative votes are not allowed.";
+                }
+                if let Some(max_votes) = rules.max_votes {
+                    ballot_message +=
+                        &format!(" You may give each option at most {max_votes} votes.");
+                }
+                if rules.banking {
+                    ballot_message += " Tokens you do not spend are banked.";
+                }
+            }
+            ballot_message += &format!("\n{}", method.ballot_example());
+
+            if let SessionMethod::Quadratic { rules } = method
+                && rules.banking
+                && let Err(err) = self
+                    .reserve_qv_credits(&mut tx, &guild, latest_guild_session)
+                    .await
+            {
+                error!("Failed to reserve credits for session {latest_guild_session}: {err}");
+                chan_respond("Failed to start vote").await;
+                tx.rollback().await.unwrap();
+                return;
+            }
 
             let session_participants = match sqlx::query!(
-                "SELECT participant FROM session_participants WHERE session_id = ?",
+                "SELECT participant, qv_credits FROM session_participants WHERE session_id = ?",
                 latest_guild_session,
             )
             .fetch_all(&mut *tx)
@@ -761,8 +2910,13 @@ SELECT
 
             for row in session_participants.iter() {
                 let user = UserId::new(row.participant.to_string().parse::<u64>().unwrap());
+                let mut user_message = ballot_message.clone();
+                if row.qv_credits > 0 {
+                    user_message +=
+                        &format!("\nYou also have {} banked tokens to spend.", row.qv_credits);
+                }
                 match user
-                    .direct_message(&ctx, CreateMessage::new().content(ballot_message.clone()))
+                    .direct_message(&ctx, CreateMessage::new().content(user_message))
                     .await
                 {
                     Ok(_) => {
@@ -804,16 +2958,6 @@ SELECT
             // TODO probably a vote, but maybe we should check a prefix at somepoint
             let user = msg.author.id.get().to_string();
 
-            let ballot: cv::Ballot = match parse_cv_ballot(msg.content.clone()) {
-                Ok(b) => b,
-                Err(err) => {
-                    error!("Failed to parse ballot {0}: {err}", msg.content.clone());
-
-                    dm_respond(&format!("Failed to parse your ballot: {err}")).await;
-                    return;
-                }
-            };
-
             debug!("Starting vote tx");
 
             let mut vote_tx = self.database.begin().await.unwrap();
@@ -846,7 +2990,7 @@ SELECT
 
             debug!("Queried active sessions");
 
-            if sessions_in_voting_state.len() == 0 {
+            if sessions_in_voting_state.is_empty() {
                 error!(
                     "No sessions in voting state for DMing user {}.",
                     user.to_string()
@@ -866,51 +3010,80 @@ SELECT
 
             debug!("Got session");
 
-            let row = sessions_in_voting_state.get(0).unwrap();
+            let row = sessions_in_voting_state.first().unwrap();
             let session_channel = ChannelId::new(row.server_channel.parse::<u64>().unwrap());
             let session_id = row.id.unwrap();
 
-            // TODO: Include options in parsing?
-            let option_id_to_option = self.option_id_to_option(&mut vote_tx, session_id).await;
-
-            if ballot.len() != option_id_to_option.len() {
-                dm_respond(&format!(
-                    "Your ballot has {} entries but it must have {} (the same number as the number of options)", ballot.len(), option_id_to_option.len(),
-                ))
-                .await;
-                vote_tx.rollback().await.unwrap();
-                return;
-            }
-            debug!("Verified ballot len");
-
-            // TODO: parse, don't validate
-            for ballot_entry in ballot.iter() {
-                if !option_id_to_option.contains_key(&ballot_entry) {
-                    dm_respond(&format!(
-                        "Your ballot contains in invalid key: {ballot_entry}"
-                    ))
+            let method = match self.session_method(&mut vote_tx, session_id).await {
+                Some(method) => method,
+                None => {
+                    dm_respond(
+                        "Your voting session has an unsupported voting method. Contact the admin.",
+                    )
                     .await;
                     vote_tx.rollback().await.unwrap();
                     return;
                 }
-            }
-            debug!("Verified ballot keys");
+            };
+
+            let option_id_to_option = self.option_id_to_option(&mut vote_tx, session_id).await;
+
+            // Voters may spend the credits they brought on top of the budget.
+            let method = match method {
+                SessionMethod::Quadratic { rules } => {
+                    let credits = self
+                        .participant_qv_credits(&mut vote_tx, session_id)
+                        .await
+                        .get(&user)
+                        .copied()
+                        .unwrap_or(0);
+                    SessionMethod::Quadratic {
+                        rules: rules.with_credits(credits),
+                    }
+                }
+                method => method,
+            };
+
+            let ballot =
+                match parse_ballot_entries(&method, msg.content.clone(), &option_id_to_option) {
+                    Ok(b) => b,
+                    Err(err) => {
+                        error!("Failed to parse ballot {0}: {err}", msg.content.clone());
+
+                        dm_respond(&format!("Failed to parse your ballot: {err}")).await;
+                        vote_tx.rollback().await.unwrap();
+                        return;
+                    }
+                };
+            debug!("Verified ballot");
 
             let author_id = msg.author.id.get().to_string();
 
-            // TODO: transaction
-            for (rank, option_id) in ballot.iter().enumerate() {
-                let rank_i64: i64 = i64::try_from(rank).unwrap();
+            // Replace any earlier ballot so that no stale entries survive a re-vote.
+            if let Err(err) = sqlx::query!(
+                "DELETE FROM session_votes WHERE session_id = ? AND voter = ?",
+                session_id,
+                author_id,
+            )
+            .execute(&mut *vote_tx)
+            .await
+            {
+                error!("Failed to clear previous vote: {err}");
+                dm_respond(&format!("Failed to insert your vote: {err}")).await;
+                vote_tx.rollback().await.unwrap();
+                return;
+            }
+
+            for entry in ballot.iter() {
                 match sqlx::query!(
-                    "INSERT INTO session_votes (session_id, voter, ballot_option_id, rank)
-                     VALUES (?,?,?,?)
-                     ON CONFLICT(session_id, voter, ballot_option_id)
-                       DO UPDATE SET rank=excluded.rank
+                    "INSERT INTO session_votes (session_id, voter, ballot_option_id, rank, votes)
+                     VALUES (?,?,?,?,?)
 ",
                     session_id,
                     author_id,
-                    option_id,
-                    rank_i64,
+                    entry.option_id,
+                    entry.rank,
+                    entry.votes,
                 )
                 .execute(&mut *vote_tx)
                 .await
@@ -927,11 +3100,17 @@ SELECT
 
             debug!("Inserted votes");
 
-            dm_respond(&format!("Ballot recorded: {:?}", ballot)).await;
+            dm_respond(&format!("Ballot recorded: {}", msg.content.trim())).await;
 
-            if self.is_voting_complete(&mut vote_tx, session_id).await {
-                self.finish_vote(&mut vote_tx, &ctx, session_id, session_channel)
-                    .await;
+            if self.is_voting_complete(&mut vote_tx, session_id).await
+                && let Err(err) = self
+                    .finish_vote(&mut vote_tx, &ctx, session_id, session_channel)
+                    .await
+            {
+                error!("Failed to finish session {session_id}: {err}");
+                dm_respond("Failed to finish the vote, so your ballot was rolled back. Please send it again.").await;
+                vote_tx.rollback().await.unwrap();
+                return;
             }
 
             if let Err(err) = vote_tx.commit().await {
@@ -942,15 +3121,346 @@ SELECT
             debug!("Committed vote tx");
         }
         /////////////////////////////////////////////////////////////
+        // ^weight
+        /////////////////////////////////////////////////////////////
+        else if let Some(weight_args) = msg.content.strip_prefix("^weight")
+            && (weight_args.is_empty() || weight_args.starts_with(char::is_whitespace))
+        {
+            let guild_id = match msg.guild_id {
+                Some(id) => id,
+                None => {
+                    info!("Received weight message with no guild ID");
+                    return;
+                }
+            };
+            let guild = guild_id.to_string();
+
+            // Guilds aren't cached without the GUILDS intent, so ask for the
+            // roles directly.
+            let can_manage = match (
+                msg.member(&ctx).await,
+                guild_id.to_partial_guild(&ctx.http).await,
+            ) {
+                (Ok(member), Ok(partial_guild)) => can_manage_guild(
+                    guild_id,
+                    partial_guild.owner_id == member.user.id,
+                    &partial_guild
+                        .roles
+                        .iter()
+                        .map(|(id, role)| (*id, role.permissions))
+                        .collect(),
+                    &member.roles,
+                ),
+                (Err(err), _) | (_, Err(err)) => {
+                    error!(
+                        "Failed to look up the permissions of member {}: {err}",
+                        msg.author.id
+                    );
+                    false
+                }
+            };
+            if !can_manage {
+                chan_respond("Only members who can manage the server can set ballot weights.")
+                    .await;
+                return;
+            }
+
+            let (voter, weight) = match (
+                msg.mentions.as_slice(),
+                weight_args.split_whitespace().last().map(parse_weight),
+            ) {
+                ([voter], Some(Ok(weight))) => (voter, weight),
+                (_, Some(Err(err))) => {
+                    chan_respond(&format!("Failed to set the weight: {err}")).await;
+                    return;
+                }
+                (_, _) => {
+                    chan_respond("Usage: `^weight @member 1/2`").await;
+                    return;
+                }
+            };
+
+            let mut weight_tx = self.database.begin().await.unwrap();
+            let session_id = match self
+                .latest_guild_session(&mut weight_tx, &ctx, guild.clone(), msg.channel_id)
+                .await
+            {
+                Some(session) => session,
+                None => {
+                    weight_tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+
+            if !matches!(
+                self.session_method(&mut weight_tx, session_id).await,
+                Some(SessionMethod::Condorcet { .. })
+            ) {
+                chan_respond("Only `cv` sessions count ballot weights.").await;
+                weight_tx.rollback().await.unwrap();
+                return;
+            }
+
+            let participant = voter.id.get().to_string();
+            let (numer, denom) = (i64::from(*weight.numer()), i64::from(*weight.denom()));
+            match sqlx::query!(
+                "UPDATE session_participants SET weight_numer = ?, weight_denom = ? WHERE session_id = ? AND participant = ?",
+                numer,
+                denom,
+                session_id,
+                participant,
+            )
+            .execute(&mut *weight_tx)
+            .await
+            {
+                Ok(result) if result.rows_affected() == 0 => {
+                    chan_respond(&format!(
+                        "{} is not participating in the current session.",
+                        voter.name
+                    ))
+                    .await;
+                    weight_tx.rollback().await.unwrap();
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    error!("Failed to set weight of {participant} in session {session_id}: {err}");
+                    chan_respond("Failed to set the weight.").await;
+                    weight_tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+
+            weight_tx.commit().await.unwrap();
+            info!("Set weight of {participant} in session {session_id} to {weight}");
+            chan_respond(&format!(
+                "{}'s ballot now counts {weight} times.",
+                voter.name
+            ))
+            .await;
+        }
+        /////////////////////////////////////////////////////////////
+        // ^credits
+        /////////////////////////////////////////////////////////////
+        else if msg.content.eq("^credits") {
+            let guild = match msg.guild_id {
+                Some(id) => id.to_string(),
+                None => {
+                    info!("Received credits message with no guild ID");
+                    return;
+                }
+            };
+            let member = msg.author.id.get().to_string();
+
+            let mut tx = self.database.begin().await.unwrap();
+            let credits = match sqlx::query!(
+                "SELECT credits FROM qv_credits WHERE discord_server = ? AND member = ?",
+                guild,
+                member,
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            {
+                Ok(row) => row.map_or(0, |row| row.credits),
+                Err(err) => {
+                    error!("Failed to look up credits of {member} in guild {guild}: {err}");
+                    chan_respond("Failed to look up your credits.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+            tx.commit().await.unwrap();
+
+            chan_respond(&format!(
+                "You have {credits} banked tokens for quadratic votes with `bank` in this server."
+            ))
+            .await;
+        }
+        /////////////////////////////////////////////////////////////
+        // ^compare
+        /////////////////////////////////////////////////////////////
+        else if msg.content.eq("^compare") {
+            let guild = match msg.guild_id {
+                Some(id) => id.to_string(),
+                None => {
+                    info!("Received compare message with no guild ID");
+                    return;
+                }
+            };
+            let channel = msg.channel_id.to_string();
+
+            let mut tx = self.database.begin().await.unwrap();
+            let session_id = match sqlx::query!(
+                "SELECT id FROM voting_session WHERE discord_server = ? AND server_channel = ? AND status = 'finished' ORDER BY id DESC LIMIT 1",
+                guild,
+                channel,
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            {
+                Ok(Some(row)) => row.id,
+                Ok(None) => {
+                    chan_respond("No finished voting session in this channel to compare.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+                Err(err) => {
+                    error!("Failed to find a finished session for guild {guild}, channel {channel}: {err}");
+                    chan_respond("Failed to compare methods.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+
+            let method = match self.session_method(&mut tx, session_id).await {
+                Some(method) => method,
+                None => {
+                    chan_respond("This session has an unsupported voting method.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+            let entries = self.ballot_entries(&mut tx, session_id).await;
+            let option_id_to_option = self.option_id_to_option(&mut tx, session_id).await;
+            let weights = self.participant_weights(&mut tx, session_id).await;
+            let qv_credits = self.participant_qv_credits(&mut tx, session_id).await;
+            tx.commit().await.unwrap();
+
+            let num_choices = u32::try_from(option_id_to_option.len()).unwrap();
+            // Some methods search every ordering, so keep them off the
+            // message handler.
+            let rows = match tokio::task::spawn_blocking(move || {
+                comparison_rows(&method, num_choices, &entries, &weights, &qv_credits)
+            })
+            .await
+            {
+                Ok(rows) => rows,
+                Err(err) => {
+                    error!("Failed to compare methods for session {session_id}: {err}");
+                    chan_respond("Failed to compare methods.").await;
+                    return;
+                }
+            };
+            info!("Comparison for session {session_id}: {rows:?}");
+            chan_respond(&format!(
+                "Every method on the ballots of the last vote here:\n{}",
+                compare::report(&rows, &option_id_to_option)
+            ))
+            .await;
+        }
+        /////////////////////////////////////////////////////////////
+        // ^margin
+        /////////////////////////////////////////////////////////////
+        else if msg.content.eq("^margin") {
+            let guild = match msg.guild_id {
+                Some(id) => id.to_string(),
+                None => {
+                    info!("Received margin message with no guild ID");
+                    return;
+                }
+            };
+            let channel = msg.channel_id.to_string();
+
+            let mut tx = self.database.begin().await.unwrap();
+            let session_id = match sqlx::query!(
+                "SELECT id FROM voting_session WHERE discord_server = ? AND server_channel = ? AND status = 'finished' ORDER BY id DESC LIMIT 1",
+                guild,
+                channel,
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            {
+                Ok(Some(row)) => row.id,
+                Ok(None) => {
+                    chan_respond("No finished voting session in this channel to analyse.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+                Err(err) => {
+                    error!("Failed to find a finished session for guild {guild}, channel {channel}: {err}");
+                    chan_respond("Failed to analyse the vote.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+
+            let (tie_breaker, strength) = match self.session_method(&mut tx, session_id).await {
+                Some(SessionMethod::Condorcet {
+                    tie_breaker,
+                    strength,
+                }) => (tie_breaker, strength),
+                _ => {
+                    chan_respond("Only `cv` sessions have a margin of victory.").await;
+                    tx.rollback().await.unwrap();
+                    return;
+                }
+            };
+            let entries = self.ballot_entries(&mut tx, session_id).await;
+            let weights = self.participant_weights(&mut tx, session_id).await;
+            let option_id_to_option = self.option_id_to_option(&mut tx, session_id).await;
+            tx.commit().await.unwrap();
+
+            let num_choices = u32::try_from(option_id_to_option.len()).unwrap();
+            let result = match cv::weighted_vote(
+                num_choices,
+                weighted_ranked_ballots(&entries, &weights),
+                &tie_breaker,
+                strength,
+            ) {
+                Ok(result) => result,
+                Err(err) => {
+                    chan_respond(&format!("Failed to analyse the vote: {err}")).await;
+                    return;
+                }
+            };
+            // Resample with the session's published tie-breaking seed so
+            // anyone can reproduce the estimate.
+            let seed = match tie_breaker {
+                cv::TieBreaker::RandomBallot { seed } | cv::TieBreaker::RandomOrder { seed } => {
+                    seed
+                }
+                cv::TieBreaker::None => 0,
+            };
+            // Resampling is CPU-bound, so keep it off the message handler.
+            let num_ballots = result.ballots.len();
+            let (margin, bootstrap) = match tokio::task::spawn_blocking(move || {
+                (
+                    sensitivity::margin(&result),
+                    sensitivity::bootstrap(&result, sensitivity::RESAMPLES, seed),
+                )
+            })
+            .await
+            {
+                Ok(analysis) => analysis,
+                Err(err) => {
+                    error!("Failed to analyse session {session_id}: {err}");
+                    chan_respond("Failed to analyse the vote.").await;
+                    return;
+                }
+            };
+            info!("Sensitivity of session {session_id}: {margin:?}, {bootstrap:?}");
+            chan_respond(&sensitivity::describe(
+                &margin,
+                &bootstrap,
+                num_ballots,
+                &option_id_to_option,
+            ))
+            .await;
+        }
+        /////////////////////////////////////////////////////////////
         // Catch-all / help
         /////////////////////////////////////////////////////////////
         else if msg.content.starts_with("^") {
             chan_respond("Unknown command.
 Options:
-- `^newsession`: Starts a new voting session.
+- `^newsession [cv [ballot | random | none] [wv | margins | ratio | combined] | rp [ballot | random | none] | queue [ballot | random | none] | irv | stv <seats> | borda | copeland | minimax [wv | margins] | baldwin | nanson | kemeny | approval | score | star | mj | qv <tokens> [positive] [cap <n>] [bank]]`: Starts a new voting session. Defaults to ranked (`cv`) voting, with Schulze ties broken by random ballot (`ballot`), a random order (`random`) or not at all (`none`) and pairwise wins measured by winning votes (`wv`), margins, ratio or winning then losing votes (`combined`); `rp` counts the same ballots with Ranked Pairs; `queue` orders every option by Schulze proportional ranking; `stv 3` elects three options by single transferable vote; `borda`, `copeland`, `minimax [wv | margins]`, `baldwin` and `nanson` count the same ranked ballots with those methods; `kemeny` finds the exact Kemeny-Young ranking (at most 10 options); `approval`, `score` and `star` take approval, 0-5 score and STAR ballots; `mj` grades options from Reject to Great by Majority Judgment; `irv` uses instant-runoff on the same ranked ballots; `qv 100` starts a quadratic vote with 100 tokens per voter, optionally followed by `positive` (no votes against), `cap 5` (at most 5 votes per option) and `bank` (keep unspent tokens, up to one budget, for the next `bank` session).
 - `^participate`: Join the voting session.
 - `^suggest`: Add a candidate to the voting session. Max of one candidate per user. Auto-participates.
+- `^weight @member 1/2`: Count a participant's ballot in this `cv` session with a weight, e.g. `1/2` for each member of a household sharing a vote or `2` for a proxy. Needs the Manage Server permission.
 - `^vote`: Start voting. Once all participants have responded, the result will be posted to this channel.
+- `^credits`: Show the tokens you have banked for quadratic votes with `bank`.
+- `^compare`: Count the ballots of the last finished vote in this channel with every method and show where they disagree.
+- `^margin`: For the last finished `cv` vote in this channel, show at most how many ballots would have changed the winner and how often each option wins when the ballots are resampled.
 ").await;
         }
     }
@@ -1031,7 +3541,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     if let Err(err) = client.start().await {
         error!("Client start failed: {err:?}");
-        return Err(err)?;
+        return Err(err.into());
     }
 
     Ok(())
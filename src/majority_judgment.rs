@@ -0,0 +1,393 @@
+pub mod mj {
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use std::fmt;
+
+    pub type BallotChoice = u32;
+    /// Grades by option. Options left off a ballot are graded `Reject`.
+    pub type Ballot = BTreeMap<BallotChoice, Grade>;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Grade {
+        Reject,
+        Poor,
+        Ok,
+        Good,
+        Great,
+    }
+
+    impl Grade {
+        /// Every grade from worst to best.
+        pub const ALL: [Grade; 5] = [
+            Grade::Reject,
+            Grade::Poor,
+            Grade::Ok,
+            Grade::Good,
+            Grade::Great,
+        ];
+
+        /// Parses a grade name, ignoring case.
+        pub fn parse(grade_str: &str) -> Option<Grade> {
+            Grade::ALL
+                .into_iter()
+                .find(|grade| grade.to_string().eq_ignore_ascii_case(grade_str.trim()))
+        }
+
+        // Its position in `Grade::ALL`, which follows the declaration order.
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    impl fmt::Display for Grade {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                Grade::Reject => "Reject",
+                Grade::Poor => "Poor",
+                Grade::Ok => "OK",
+                Grade::Good => "Good",
+                Grade::Great => "Great",
+            };
+            write!(f, "{name}")
+        }
+    }
+
+    // Grades are stored in the database by their position in `Grade::ALL`.
+    impl From<Grade> for i64 {
+        fn from(grade: Grade) -> i64 {
+            grade as i64
+        }
+    }
+
+    impl TryFrom<i64> for Grade {
+        type Error = VoteError;
+
+        fn try_from(value: i64) -> Result<Grade, VoteError> {
+            usize::try_from(value)
+                .ok()
+                .and_then(|index| Grade::ALL.get(index).copied())
+                .ok_or(VoteError::InvalidGrade(value))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Every option sharing the best majority value.
+        pub winners: Vec<BallotChoice>,
+        /// How many ballots gave each option each grade, indexed like
+        /// `Grade::ALL`.
+        pub distributions: BTreeMap<BallotChoice, [u64; 5]>,
+        /// The median grades of each option in the order the tie-breaking
+        /// procedure removes them. The first is the option's median grade.
+        pub majority_values: BTreeMap<BallotChoice, Vec<Grade>>,
+        pub num_ballots: u64,
+    }
+
+    impl std::fmt::Display for VoteBreakdown {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str(&format!(
+                "Winners - {:?}. Grade distributions: {:?} from {} ballots",
+                self.winners, self.distributions, self.num_ballots
+            ))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum VoteError {
+        NoBallots,
+        /// Ballot `ballot` (numbered from 0) grades an option that does not
+        /// exist.
+        UnknownOption {
+            ballot: usize,
+            choice: BallotChoice,
+        },
+        /// A stored grade that is not a position in `Grade::ALL`.
+        InvalidGrade(i64),
+    }
+
+    impl fmt::Display for VoteError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                VoteError::NoBallots => write!(f, "Must have at least one ballot to vote"),
+                VoteError::UnknownOption { ballot, choice } => {
+                    write!(f, "Ballot {ballot} grades unknown option {choice}")
+                }
+                VoteError::InvalidGrade(value) => write!(f, "{value} is not a valid grade"),
+            }
+        }
+    }
+
+    impl Error for VoteError {}
+
+    #[test]
+    fn test_majority_value() {
+        use Grade::*;
+
+        // Lower median of an even count.
+        assert_eq!(
+            majority_value(vec![Great, Poor, Good, Ok]),
+            vec![Ok, Good, Poor, Great]
+        );
+        assert_eq!(
+            majority_value(vec![Good, Good, Reject]),
+            vec![Good, Reject, Good]
+        );
+        assert_eq!(majority_value(vec![]), vec![]);
+    }
+
+    // Repeatedly takes the (lower) median grade out of `grades`, returning
+    // the medians in the order they were removed. Comparing these sequences
+    // lexicographically is the Majority Judgment tie-breaking procedure.
+    fn majority_value(mut grades: Vec<Grade>) -> Vec<Grade> {
+        grades.sort();
+        let mut medians = Vec::with_capacity(grades.len());
+        while !grades.is_empty() {
+            medians.push(grades.remove((grades.len() - 1) / 2));
+        }
+        return medians;
+    }
+
+    #[test]
+    fn test_vote() {
+        use Grade::*;
+
+        struct Case {
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+            expected_winners: Vec<BallotChoice>,
+            expected_medians: Vec<Grade>,
+        }
+
+        let cases = [
+            Case {
+                num_choices: 3,
+                ballots: vec![
+                    Ballot::from([(0, Great), (1, Good), (2, Poor)]),
+                    Ballot::from([(0, Reject), (1, Good), (2, Ok)]),
+                    Ballot::from([(0, Good), (1, Ok)]),
+                ],
+                expected_winners: vec![1],
+                expected_medians: vec![Good, Good, Poor],
+            },
+            // 0 and 1 share the median Good. Removing it leaves 0 with Great
+            // and Reject, 1 with Good and Ok; the lower medians are Reject
+            // and Ok, so 1 wins.
+            Case {
+                num_choices: 2,
+                ballots: vec![
+                    Ballot::from([(0, Great), (1, Good)]),
+                    Ballot::from([(0, Good), (1, Good)]),
+                    Ballot::from([(0, Reject), (1, Ok)]),
+                ],
+                expected_winners: vec![1],
+                expected_medians: vec![Good, Good],
+            },
+            // Identical distributions tie.
+            Case {
+                num_choices: 2,
+                ballots: vec![
+                    Ballot::from([(0, Great), (1, Poor)]),
+                    Ballot::from([(0, Poor), (1, Great)]),
+                ],
+                expected_winners: vec![0, 1],
+                expected_medians: vec![Poor, Poor],
+            },
+        ];
+
+        for case in cases.iter() {
+            let result = vote(case.num_choices, case.ballots.clone()).unwrap();
+            assert_eq!(result.winners, case.expected_winners);
+            let medians: Vec<Grade> = result
+                .majority_values
+                .values()
+                .map(|values| values[0])
+                .collect();
+            assert_eq!(medians, case.expected_medians);
+        }
+
+        assert_eq!(vote(2, vec![]), Err(VoteError::NoBallots));
+        assert_eq!(
+            vote(2, vec![Ballot::from([(2, Good)])]),
+            Err(VoteError::UnknownOption {
+                ballot: 0,
+                choice: 2
+            })
+        );
+    }
+
+    pub fn vote(num_choices: u32, ballots: Vec<Ballot>) -> Result<VoteBreakdown, VoteError> {
+        if ballots.is_empty() {
+            return Err(VoteError::NoBallots);
+        }
+        for (i, ballot) in ballots.iter().enumerate() {
+            if let Some(choice) = ballot.keys().find(|choice| **choice >= num_choices) {
+                return Err(VoteError::UnknownOption {
+                    ballot: i,
+                    choice: *choice,
+                });
+            }
+        }
+
+        let mut distributions = BTreeMap::new();
+        let mut majority_values = BTreeMap::new();
+        for choice in 0..num_choices {
+            let grades: Vec<Grade> = ballots
+                .iter()
+                .map(|ballot| ballot.get(&choice).copied().unwrap_or(Grade::Reject))
+                .collect();
+            let mut distribution = [0; 5];
+            for grade in grades.iter() {
+                distribution[grade.index()] += 1;
+            }
+            distributions.insert(choice, distribution);
+            majority_values.insert(choice, majority_value(grades));
+        }
+
+        let best = majority_values.values().max().cloned().unwrap_or_default();
+        let winners = majority_values
+            .iter()
+            .filter(|(_, values)| **values == best)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        return Ok(VoteBreakdown {
+            winners,
+            distributions,
+            majority_values,
+            num_ballots: vm::count(ballots.len()),
+        });
+    }
+
+    // Explains how the median-grade procedure split `winner` from
+    // `runner_up` when they share a median grade.
+    fn tie_break_str(
+        result: &VoteBreakdown,
+        winner: BallotChoice,
+        runner_up: BallotChoice,
+        option_id_to_option: &BTreeMap<vm::OptionId, String>,
+    ) -> Option<String> {
+        let winner_values = &result.majority_values[&winner];
+        let runner_up_values = &result.majority_values[&runner_up];
+        let removed = winner_values
+            .iter()
+            .zip(runner_up_values.iter())
+            .position(|(a, b)| a != b)?;
+        if removed == 0 {
+            return None;
+        }
+
+        let winner_name = vm::option_name(winner, option_id_to_option);
+        let runner_up_name = vm::option_name(runner_up, option_id_to_option);
+        let grades = match removed {
+            1 => "grade".to_string(),
+            _ => format!("{removed} grades"),
+        };
+        return Some(format!(
+            "{winner_name} and {runner_up_name} share the median {}; after removing the median {grades} from each, {winner_name} has {} against {runner_up_name}'s {}.",
+            winner_values[0], winner_values[removed], runner_up_values[removed]
+        ));
+    }
+
+    /// Majority Judgment: each voter grades every option and the best median
+    /// grade wins, ties broken by repeatedly removing the shared median.
+    pub struct MajorityJudgment;
+
+    impl vm::VotingMethod for MajorityJudgment {
+        type Ballot = Ballot;
+        type Details = VoteBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            "Majority Judgment"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking: vm::ranking_by_score(breakdown.majority_values.clone()),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let median = |choice: &BallotChoice| result.majority_values[choice][0];
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.as_slice() {
+                [] => "No Majority Judgment winner.".to_string(),
+                [winner] => format!(
+                    "Majority Judgment winner: {winner} (median {}).",
+                    median(&result.winners[0])
+                ),
+                _ => format!(
+                    "Tied Majority Judgment winners: {} (median {}), with identical grades.",
+                    winners.join(", "),
+                    median(&result.winners[0])
+                ),
+            };
+
+            if let ([winner], Some([runner_up, ..])) = (
+                result.winners.as_slice(),
+                outcome.ranking.get(1).map(Vec::as_slice),
+            ) && let Some(tie_break) =
+                tie_break_str(result, *winner, *runner_up, option_id_to_option)
+            {
+                message += &format!("\n{tie_break}");
+            }
+
+            let names: Vec<String> = Grade::ALL.iter().map(Grade::to_string).collect();
+            message += &format!("\nGrades ({}):", names.join("/"));
+            for (choice, distribution) in result.distributions.iter() {
+                let counts: Vec<String> = distribution.iter().map(u64::to_string).collect();
+                message += &format!(
+                    "\n- {}: {}, median {}",
+                    vm::option_label(*choice, option_id_to_option),
+                    counts.join("/"),
+                    median(choice)
+                );
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+        use Grade::*;
+
+        let ballots = vec![
+            Ballot::from([(0, Great), (1, Good)]),
+            Ballot::from([(0, Good), (1, Good)]),
+            Ballot::from([(0, Reject), (1, Ok)]),
+        ];
+        let outcome = MajorityJudgment.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![1]);
+        assert_eq!(outcome.ranking, vec![vec![1], vec![0], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            MajorityJudgment.describe(&outcome, &options),
+            "Majority Judgment winner: **Emma** (median Good).
+Emma and Dune share the median Good; after removing the median grade from each, Emma has OK against Dune's Reject.
+Grades (Reject/Poor/OK/Good/Great):
+- 0 (Dune): 1/0/0/1/1, median Good
+- 1 (Emma): 0/0/1/2/0, median Good
+- 2: 3/0/0/0/0, median Reject"
+        );
+    }
+}
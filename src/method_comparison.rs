@@ -0,0 +1,300 @@
+pub mod compare {
+    use crate::baldwin_method::baldwin;
+    use crate::borda_count::borda;
+    use crate::condorcet_voting::cv;
+    use crate::copeland_method::copeland;
+    use crate::kemeny_young::kemeny;
+    use crate::minimax_method::minimax;
+    use crate::nanson_method::nanson;
+    use crate::ranked_choice_voting::rcv;
+    use crate::ranked_pairs::rp;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    /// One method's result on the shared ballots, or why it could not run.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Row {
+        pub method: String,
+        pub result: Result<(Vec<vm::OptionId>, Vec<Vec<vm::OptionId>>), String>,
+    }
+
+    /// Tallies `ballots` with `method` for a comparison row.
+    pub fn row<M: vm::VotingMethod>(method: &M, num_choices: u32, ballots: Vec<M::Ballot>) -> Row {
+        return Row {
+            method: method.name().to_string(),
+            result: method
+                .tally(num_choices, ballots)
+                .map(|outcome| (outcome.winners, outcome.ranking))
+                .map_err(|err| err.to_string()),
+        };
+    }
+
+    #[test]
+    fn test_ranked_from_ratings() {
+        let ratings = BTreeMap::from([(0, 3), (2, 5), (3, 3), (4, -1)]);
+        // 1 is unrated and so counts as 0, below 0 and 3 but above 4.
+        assert_eq!(
+            ranked_from_ratings(5, &ratings),
+            vec![vec![2], vec![0, 3], vec![1], vec![4]]
+        );
+        assert_eq!(ranked_from_ratings(2, &BTreeMap::new()), vec![vec![0, 1]]);
+    }
+
+    /// Turns a rated ballot (scores, grades, approvals or quadratic votes by
+    /// option) into a ranked one: higher ratings rank higher, equal ratings
+    /// rank equally and unrated options count as 0.
+    pub fn ranked_from_ratings(
+        num_choices: u32,
+        ratings: &BTreeMap<vm::OptionId, i64>,
+    ) -> cv::Ballot {
+        return vm::ranking_by_score(
+            (0..num_choices).map(|choice| (choice, ratings.get(&choice).copied().unwrap_or(0))),
+        );
+    }
+
+    /// Every method that counts ranked ballots. Ties are left unbroken so
+    /// methods only differ where their rules do.
+    pub fn ranked_rows(num_choices: u32, ballots: &[cv::Ballot]) -> Vec<Row> {
+        let weighted: Vec<cv::WeightedBallot> = ballots
+            .iter()
+            .map(|ballot| cv::WeightedBallot {
+                weight: cv::Weight::from(1),
+                ballot: ballot.clone(),
+            })
+            .collect();
+        return weighted_ranked_rows(num_choices, &weighted);
+    }
+
+    #[test]
+    fn test_weighted_ranked_rows() {
+        // Two half-weight ballots for 1 lose to one full ballot for 0 only
+        // once their weights count.
+        let ballots = vec![
+            cv::WeightedBallot {
+                weight: cv::Weight::new(1, 2),
+                ballot: vec![vec![1], vec![0]],
+            },
+            cv::WeightedBallot {
+                weight: cv::Weight::new(1, 2),
+                ballot: vec![vec![1], vec![0]],
+            },
+            cv::WeightedBallot {
+                weight: cv::Weight::from(2),
+                ballot: vec![vec![0], vec![1]],
+            },
+        ];
+        let rows = weighted_ranked_rows(2, &ballots);
+        assert_eq!(rows[0].method, "Condorcet/Schulze");
+        assert_eq!(rows[0].result.as_ref().unwrap().0, vec![0]);
+        assert_eq!(rows[4].method, "Ranked Pairs (unweighted)");
+        assert_eq!(rows[4].result.as_ref().unwrap().0, vec![1]);
+
+        let unweighted = ranked_rows(2, &[vec![vec![1], vec![0]]]);
+        assert_eq!(unweighted[4].method, "Ranked Pairs");
+    }
+
+    /// `ranked_rows` with Condorcet/Schulze counting each ballot's weight.
+    /// The other methods count every ballot once, and say so in their names
+    /// when any weight is not 1.
+    pub fn weighted_ranked_rows(num_choices: u32, weighted: &[cv::WeightedBallot]) -> Vec<Row> {
+        let mut rows: Vec<Row> = [
+            cv::Strength::WinningVotes,
+            cv::Strength::Margins,
+            cv::Strength::Ratio,
+            cv::Strength::Combined,
+        ]
+        .into_iter()
+        .map(|strength| {
+            row(
+                &cv::WeightedCondorcet {
+                    tie_breaker: cv::TieBreaker::None,
+                    strength,
+                },
+                num_choices,
+                weighted.to_vec(),
+            )
+        })
+        .collect();
+        let schulze_rows = rows.len();
+
+        let ballots: Vec<cv::Ballot> = weighted.iter().map(|w| w.ballot.clone()).collect();
+        let ballots = ballots.as_slice();
+        rows.push(row(
+            &rp::RankedPairs {
+                tie_breaker: cv::TieBreaker::None,
+            },
+            num_choices,
+            ballots.to_vec(),
+        ));
+        rows.push(
+            match ballots
+                .iter()
+                .any(|ballot| ballot.iter().any(|tier| tier.len() > 1))
+            {
+                true => Row {
+                    method: "Instant-runoff".to_string(),
+                    result: Err("needs ballots without equal ranks".to_string()),
+                },
+                false => row(
+                    &rcv::InstantRunoff,
+                    num_choices,
+                    ballots.iter().map(|ballot| ballot.concat()).collect(),
+                ),
+            },
+        );
+        rows.push(row(&borda::Borda, num_choices, ballots.to_vec()));
+        rows.push(row(&copeland::Copeland, num_choices, ballots.to_vec()));
+        for strength in [minimax::Strength::WinningVotes, minimax::Strength::Margins] {
+            rows.push(row(
+                &minimax::Minimax { strength },
+                num_choices,
+                ballots.to_vec(),
+            ));
+        }
+        rows.push(row(&baldwin::Baldwin, num_choices, ballots.to_vec()));
+        rows.push(row(&nanson::Nanson, num_choices, ballots.to_vec()));
+        rows.push(row(&kemeny::KemenyYoung, num_choices, ballots.to_vec()));
+
+        if weighted
+            .iter()
+            .any(|ballot| ballot.weight != cv::Weight::from(1))
+        {
+            for row in rows.iter_mut().skip(schulze_rows) {
+                row.method += " (unweighted)";
+            }
+        }
+        return rows;
+    }
+
+    #[test]
+    fn test_report() {
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        let ok = |method: &str, winners: Vec<vm::OptionId>, ranking: Vec<Vec<vm::OptionId>>| Row {
+            method: method.to_string(),
+            result: Ok((winners, ranking)),
+        };
+
+        let rows = vec![
+            ok("Schulze", vec![0], vec![vec![0], vec![1]]),
+            ok("Borda", vec![0], vec![vec![0], vec![1]]),
+        ];
+        assert_eq!(
+            report(&rows, &options),
+            "```
+Method   Winner  Ranking
+Schulze  Dune    Dune > Emma
+Borda    Dune    Dune > Emma
+```
+Every method picks **Dune**."
+        );
+
+        let rows = vec![
+            ok("Schulze", vec![0], vec![vec![0], vec![1]]),
+            ok("Instant-runoff", vec![1], vec![vec![1], vec![0]]),
+            ok("Borda", vec![0, 1], vec![vec![0, 1]]),
+            Row {
+                method: "Kemeny-Young".to_string(),
+                result: Err("too many options".to_string()),
+            },
+        ];
+        assert_eq!(
+            report(&rows, &options),
+            "```
+Method            Winner       Ranking
+Schulze           Dune         Dune > Emma
+! Instant-runoff  Emma         Emma > Dune
+! Borda           Dune = Emma  Dune = Emma
+Kemeny-Young      -            skipped: too many options
+```
+The methods disagree:
+- **Dune**: Schulze
+- **Emma**: Instant-runoff
+- **Dune = Emma**: Borda"
+        );
+    }
+
+    /// A table of every row's winner and ranking. Rows whose winner differs
+    /// from the first row's are marked with `!` and the disagreement is
+    /// summarised below.
+    pub fn report(rows: &[Row], option_id_to_option: &BTreeMap<vm::OptionId, String>) -> String {
+        let winners_str = |winners: &[vm::OptionId]| {
+            winners
+                .iter()
+                .map(|choice| vm::option_name(*choice, option_id_to_option))
+                .collect::<Vec<String>>()
+                .join(" = ")
+        };
+
+        let reference = rows
+            .iter()
+            .find_map(|row| row.result.as_ref().ok().map(|(winners, _)| winners));
+        let mut table: Vec<[String; 3]> = vec![[
+            "Method".to_string(),
+            "Winner".to_string(),
+            "Ranking".to_string(),
+        ]];
+        // Methods grouped by the winners they pick, in order of first appearance.
+        let mut picks: Vec<(&Vec<vm::OptionId>, Vec<&str>)> = Vec::new();
+        for row in rows.iter() {
+            match &row.result {
+                Ok((winners, ranking)) => {
+                    let marker = match Some(winners) == reference {
+                        true => "",
+                        false => "! ",
+                    };
+                    table.push([
+                        format!("{marker}{}", row.method),
+                        winners_str(winners),
+                        vm::ranking_str(ranking, option_id_to_option),
+                    ]);
+                    match picks.iter_mut().find(|(picked, _)| *picked == winners) {
+                        Some((_, methods)) => methods.push(&row.method),
+                        None => picks.push((winners, vec![&row.method])),
+                    }
+                }
+                Err(err) => table.push([
+                    row.method.clone(),
+                    "-".to_string(),
+                    format!("skipped: {err}"),
+                ]),
+            }
+        }
+
+        let widths: Vec<usize> = (0..2)
+            .map(|column| {
+                table
+                    .iter()
+                    .map(|cells| cells[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let mut message = "```".to_string();
+        for cells in table.iter() {
+            let line = format!(
+                "{:<method$}  {:<winner$}  {}",
+                cells[0],
+                cells[1],
+                cells[2],
+                method = widths[0],
+                winner = widths[1]
+            );
+            message += &format!("\n{}", line.trim_end());
+        }
+        message += "\n```";
+
+        match picks.as_slice() {
+            [] => message += "\nNo method could count these ballots.",
+            [(winners, _)] => {
+                message += &format!("\nEvery method picks **{}**.", winners_str(winners))
+            }
+            _ => {
+                message += "\nThe methods disagree:";
+                for (winners, methods) in picks.iter() {
+                    message += &format!("\n- **{}**: {}", winners_str(winners), methods.join(", "));
+                }
+            }
+        }
+        return message;
+    }
+}
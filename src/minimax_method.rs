@@ -0,0 +1,260 @@
+pub mod minimax {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    /// How strong a head-to-head defeat is.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Strength {
+        /// Votes for the winner of the pair; 0 unless it is a defeat.
+        WinningVotes,
+        /// Votes for the winner minus votes against.
+        Margins,
+    }
+
+    impl fmt::Display for Strength {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Strength::WinningVotes => write!(f, "winning votes"),
+                Strength::Margins => write!(f, "margins"),
+            }
+        }
+    }
+
+    /// The strongest defeat an option suffers.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct WorstDefeat {
+        /// The opponent inflicting it, or `None` if nobody beats the option.
+        pub opponent: Option<cv::BallotChoice>,
+        pub votes_for: u32,
+        pub votes_against: u32,
+        pub strength: i64,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Every option sharing the weakest worst defeat.
+        pub winners: Vec<cv::BallotChoice>,
+        pub strength: Strength,
+        pub worst_defeats: BTreeMap<cv::BallotChoice, WorstDefeat>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_worst_defeats() {
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 5, 1, //
+                2, 0, 4, //
+                3, 4, 0, //
+            ],
+        );
+        let defeats = worst_defeats(&m, Strength::WinningVotes);
+        assert_eq!(defeats[&0].opponent, Some(2));
+        assert_eq!(defeats[&0].strength, 3);
+        assert_eq!(defeats[&1].strength, 5);
+        assert_eq!(defeats[&2].opponent, None);
+        assert_eq!(defeats[&2].strength, 0);
+
+        let defeats = worst_defeats(&m, Strength::Margins);
+        assert_eq!(defeats[&0].strength, 2);
+        assert_eq!(defeats[&1].strength, 3);
+        // 2 is never beaten; its closest result is the 4-4 tie with 1.
+        assert_eq!(defeats[&2].opponent, None);
+        assert_eq!(defeats[&2].strength, 0);
+    }
+
+    // Each option's strongest defeat. An option nobody beats gets strength 0
+    // under winning votes, and its closest margin (at most 0) under margins.
+    fn worst_defeats(
+        final_outranking: &nalgebra::DMatrix<u32>,
+        strength: Strength,
+    ) -> BTreeMap<cv::BallotChoice, WorstDefeat> {
+        let num_choices = final_outranking.nrows();
+        let mut worst_defeats = BTreeMap::new();
+        for runner in 0..num_choices {
+            let mut worst: Option<WorstDefeat> = None;
+            for opponent in (0..num_choices).filter(|opponent| *opponent != runner) {
+                let votes_for = final_outranking[(opponent, runner)];
+                let votes_against = final_outranking[(runner, opponent)];
+                let defeat_strength = match strength {
+                    Strength::WinningVotes if votes_for > votes_against => i64::from(votes_for),
+                    Strength::WinningVotes => 0,
+                    Strength::Margins => i64::from(votes_for) - i64::from(votes_against),
+                };
+                if worst.is_none_or(|worst| defeat_strength > worst.strength) {
+                    worst = Some(WorstDefeat {
+                        opponent: Some(vm::option_id(opponent)),
+                        votes_for,
+                        votes_against,
+                        strength: defeat_strength,
+                    });
+                }
+            }
+
+            let mut worst = worst.unwrap_or(WorstDefeat {
+                opponent: None,
+                votes_for: 0,
+                votes_against: 0,
+                strength: 0,
+            });
+            if worst.votes_for <= worst.votes_against {
+                worst.opponent = None;
+            }
+            worst_defeats.insert(vm::option_id(runner), worst);
+        }
+        return worst_defeats;
+    }
+
+    #[test]
+    fn test_vote() {
+        // Winning votes and margins disagree on these.
+        let ballots = cv::truncated_ballots();
+
+        // 2 loses 2-4 to 0 and 3-4 to 1; every other option loses with 5
+        // or more winning votes.
+        let result = vote(4, ballots.clone(), Strength::WinningVotes).unwrap();
+        assert_eq!(result.winners, vec![2]);
+        assert_eq!(result.worst_defeats[&2].opponent, Some(0));
+        assert_eq!(result.worst_defeats[&2].strength, 4);
+
+        // 3 only loses 4-5 to 2, the narrowest margin.
+        let result = vote(4, ballots, Strength::Margins).unwrap();
+        assert_eq!(result.winners, vec![3]);
+        assert_eq!(result.worst_defeats[&3].opponent, Some(2));
+        assert_eq!(result.worst_defeats[&3].strength, 1);
+
+        assert!(vote(3, vec![], Strength::Margins).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+        strength: Strength,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+        let worst_defeats = worst_defeats(&final_outranking, strength);
+
+        let weakest = worst_defeats
+            .values()
+            .map(|defeat| defeat.strength)
+            .min()
+            .unwrap_or(0);
+        let winners = worst_defeats
+            .iter()
+            .filter(|(_, defeat)| defeat.strength == weakest)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        return Ok(VoteBreakdown {
+            winners,
+            strength,
+            worst_defeats,
+            final_outranking,
+        });
+    }
+
+    /// Minimax: the option whose worst head-to-head defeat is the weakest
+    /// wins.
+    pub struct Minimax {
+        pub strength: Strength,
+    }
+
+    impl vm::VotingMethod for Minimax {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            match self.strength {
+                Strength::WinningVotes => "Minimax (winning votes)",
+                Strength::Margins => "Minimax (margins)",
+            }
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots, self.strength)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking: vm::ranking_by_score(
+                    breakdown
+                        .worst_defeats
+                        .iter()
+                        .map(|(choice, defeat)| (*choice, -defeat.strength)),
+                ),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Minimax winner ({}): {}.", result.strength, winners[0]),
+                _ => format!(
+                    "Tied Minimax winners ({}): {}.",
+                    result.strength,
+                    winners.join(", ")
+                ),
+            };
+
+            message += "\nWorst defeats:";
+            for (choice, defeat) in result.worst_defeats.iter() {
+                let label = vm::option_label(*choice, option_id_to_option);
+                message += &match defeat.opponent {
+                    Some(opponent) => format!(
+                        "\n- {label}: lost to {} {}–{} (strength {})",
+                        vm::option_name(opponent, option_id_to_option),
+                        defeat.votes_for,
+                        defeat.votes_against,
+                        defeat.strength
+                    ),
+                    None => format!("\n- {label}: never beaten (strength {})", defeat.strength),
+                };
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            cv::Ballot::from([vec![0], vec![1], vec![2]]),
+            cv::Ballot::from([vec![1], vec![0], vec![2]]),
+            cv::Ballot::from([vec![0], vec![2], vec![1]]),
+        ];
+        let method = Minimax {
+            strength: Strength::Margins,
+        };
+        let outcome = method.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            method.describe(&outcome, &options),
+            "Minimax winner (margins): **Dune**.
+Worst defeats:
+- 0 (Dune): never beaten (strength -1)
+- 1 (Emma): lost to Dune 2–1 (strength 1)
+- 2: lost to Dune 3–0 (strength 3)"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+pub mod nanson {
+    use crate::borda_count::borda;
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RoundBreakdown {
+        /// Borda points among the options still standing this round.
+        pub scores: BTreeMap<cv::BallotChoice, u64>,
+        /// Every option with fewer points than the round's average.
+        pub eliminated: Vec<cv::BallotChoice>,
+    }
+
+    impl RoundBreakdown {
+        /// The average points this round, e.g. "7.33".
+        pub fn average_str(&self) -> String {
+            let total: u64 = self.scores.values().sum();
+            let count = vm::count(self.scores.len());
+            // Hundredths, rounded half up.
+            let hundredths = (total * 200 + count) / (2 * count);
+            return format!("{}.{:0>2}", hundredths / 100, hundredths % 100);
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// The last option standing, or every option still tied at the end.
+        pub winners: Vec<cv::BallotChoice>,
+        pub rounds: Vec<RoundBreakdown>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_vote() {
+        let ballots = cv::tennessee_ballots();
+
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.winners, vec![1]);
+        // Memphis (126) and Knoxville (107) fall below the average of 150.
+        assert_eq!(result.rounds[0].eliminated, vec![0, 3]);
+        assert_eq!(result.rounds[1].eliminated, vec![2]);
+
+        // Truncated ballots where Baldwin picks 2 but Nanson drops 2 in the
+        // first round with 0.
+        let ballots = cv::truncated_ballots();
+        let result = vote(4, ballots).unwrap();
+        assert_eq!(result.winners, vec![3]);
+        assert_eq!(result.rounds[0].eliminated, vec![0, 2]);
+        assert_eq!(result.rounds[0].average_str(), "11.50");
+
+        // Everyone tied.
+        let ballots = vec![vec![vec![0], vec![1]], vec![vec![1], vec![0]]];
+        let result = vote(2, ballots).unwrap();
+        assert_eq!(result.winners, vec![0, 1]);
+        assert!(result.rounds.is_empty());
+
+        assert!(vote(3, vec![]).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+
+        let mut remaining: Vec<cv::BallotChoice> = (0..num_choices).collect();
+        let mut rounds: Vec<RoundBreakdown> = Vec::new();
+        while remaining.len() > 1 {
+            let scores = borda::scores(&final_outranking, &remaining);
+            let total: u64 = scores.values().sum();
+            let count = vm::count(remaining.len());
+
+            // Below average, compared without dividing.
+            let eliminated: Vec<cv::BallotChoice> = scores
+                .iter()
+                .filter(|(_, points)| **points * count < total)
+                .map(|(choice, _)| *choice)
+                .collect();
+            if eliminated.is_empty() {
+                break;
+            }
+            remaining.retain(|choice| !eliminated.contains(choice));
+            rounds.push(RoundBreakdown { scores, eliminated });
+        }
+
+        return Ok(VoteBreakdown {
+            winners: remaining,
+            rounds,
+            final_outranking,
+        });
+    }
+
+    /// Nanson's method: repeatedly eliminate every option with fewer Borda
+    /// points than the average, recounting among the remaining options.
+    pub struct Nanson;
+
+    impl vm::VotingMethod for Nanson {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Nanson"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+
+            // Later eliminations rank higher; options eliminated together
+            // are ranked by their points in that round.
+            let mut ranking = vec![breakdown.winners.clone()];
+            for round in breakdown.rounds.iter().rev() {
+                ranking.extend(vm::ranking_by_score(
+                    round
+                        .eliminated
+                        .iter()
+                        .map(|choice| (*choice, round.scores[choice])),
+                ));
+            }
+
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking,
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Nanson winner: {}.", winners[0]),
+                _ => format!("Tied Nanson winners: {}.", winners.join(", ")),
+            };
+
+            for (i, round) in result.rounds.iter().enumerate() {
+                let eliminated: Vec<String> = round
+                    .eliminated
+                    .iter()
+                    .map(|choice| vm::option_label(*choice, option_id_to_option))
+                    .collect();
+                message += &format!(
+                    "\nRound {}: {} (average {}). Eliminated: {}",
+                    i + 1,
+                    borda::scores_str(&round.scores, option_id_to_option),
+                    round.average_str(),
+                    eliminated.join(", ")
+                );
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            cv::Ballot::from([vec![0], vec![1], vec![2]]),
+            cv::Ballot::from([vec![1], vec![0], vec![2]]),
+            cv::Ballot::from([vec![0], vec![2], vec![1]]),
+        ];
+        let outcome = Nanson.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            Nanson.describe(&outcome, &options),
+            "Nanson winner: **Dune**.
+Round 1: 0 (Dune) 5, 1 (Emma) 3, 2 1 (average 3.00). Eliminated: 2
+Round 2: 0 (Dune) 2, 1 (Emma) 1 (average 1.50). Eliminated: 1 (Emma)"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 pub mod qv {
+    use crate::voting_method::vm;
     use std::collections::BTreeMap;
-    use std::convert::TryInto;
     use std::error::Error;
     use std::fmt;
 
@@ -8,35 +8,195 @@ pub mod qv {
     pub type Votes = i64;
     pub type Ballot = BTreeMap<BallotChoice, Votes>;
 
-    pub fn tokens_used(b: &Ballot) -> i64 {
-        // Abs is so we can support negative votes
-        // TODO: is "as" okay here?
-        b.iter().fold(0, |acc, (_, votes)| acc + votes.abs().pow(2))
+    /// The largest budget a voter can have, banked credits included. Keeps
+    /// every ballot's cost and every total well inside an i64.
+    pub const MAX_TOKENS: i64 = 1_000_000_000;
+
+    /// A session's quadratic voting rules.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Rules {
+        pub tokens_per_ballot: i64,
+        /// Whether a ballot may vote against options.
+        pub negative_votes: bool,
+        /// The most votes, for or against, a ballot may give one option.
+        pub max_votes: Option<Votes>,
+        /// Whether voters keep the tokens they leave unspent.
+        pub banking: bool,
+    }
+
+    /// A ballot and the tokens its voter banked in earlier sessions, which
+    /// they may spend on top of the session's budget.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct BankedBallot {
+        pub credits: i64,
+        pub ballot: Ballot,
+    }
+
+    impl From<Ballot> for BankedBallot {
+        fn from(ballot: Ballot) -> BankedBallot {
+            return BankedBallot { credits: 0, ballot };
+        }
+    }
+
+    impl Rules {
+        /// `tokens_per_ballot` to spend with no other restrictions: negative
+        /// votes allowed, no cap per option and no banking.
+        pub fn new(tokens_per_ballot: i64) -> Rules {
+            return Rules {
+                tokens_per_ballot,
+                negative_votes: true,
+                max_votes: None,
+                banking: false,
+            };
+        }
+
+        /// These rules for a voter bringing `credits` banked tokens, up to a
+        /// budget of `MAX_TOKENS`.
+        pub fn with_credits(&self, credits: i64) -> Rules {
+            return Rules {
+                tokens_per_ballot: self
+                    .tokens_per_ballot
+                    .saturating_add(credits)
+                    .min(MAX_TOKENS),
+                ..*self
+            };
+        }
+
+        /// The tokens a voter keeps when they leave `unspent` unspent: none
+        /// without banking, otherwise up to one budget's worth.
+        pub fn banked(&self, unspent: i64) -> i64 {
+            match self.banking {
+                true => unspent.clamp(0, self.tokens_per_ballot),
+                false => 0,
+            }
+        }
+    }
+
+    impl fmt::Display for Rules {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{} tokens per voter", self.tokens_per_ballot)?;
+            if !self.negative_votes {
+                write!(f, ", no negative votes")?;
+            }
+            if let Some(max_votes) = self.max_votes {
+                write!(f, ", at most {max_votes} votes per option")?;
+            }
+            if self.banking {
+                write!(f, ", unspent tokens are banked")?;
+            }
+            Ok(())
+        }
+    }
+
+    /// The rule a quadratic ballot breaks. Messages are addressed to the
+    /// voter.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum BallotError {
+        NegativeOption(BallotChoice),
+        UnknownOption(BallotChoice),
+        /// Votes against an option in a session that does not allow them.
+        NegativeVotes {
+            choice: BallotChoice,
+            votes: Votes,
+        },
+        /// More votes for or against one option than `Rules::max_votes`.
+        OverCap {
+            choice: BallotChoice,
+            votes: Votes,
+            max_votes: Votes,
+        },
+        /// A ballot costing more than its voter's budget. `tokens` is `None`
+        /// when the cost is too large to count.
+        OverBudget {
+            tokens: Option<i64>,
+            budget: i64,
+        },
+    }
+
+    impl fmt::Display for BallotError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                BallotError::NegativeOption(choice) => {
+                    write!(f, "Option IDs cannot be negative: {choice}")
+                }
+                BallotError::UnknownOption(choice) => {
+                    write!(f, "Your ballot contains in invalid key: {choice}")
+                }
+                BallotError::NegativeVotes { choice, votes } => write!(
+                    f,
+                    "This session does not allow negative votes, but you gave option {choice} {votes}"
+                ),
+                BallotError::OverCap {
+                    choice,
+                    votes,
+                    max_votes,
+                } => write!(
+                    f,
+                    "You gave option {choice} {votes} votes but the most per option is {max_votes}"
+                ),
+                BallotError::OverBudget {
+                    tokens: Some(tokens),
+                    budget,
+                } => write!(
+                    f,
+                    "Your ballot uses {tokens} tokens but you only have {budget}"
+                ),
+                BallotError::OverBudget {
+                    tokens: None,
+                    budget,
+                } => write!(
+                    f,
+                    "Your ballot uses more tokens than can be counted but you only have {budget}"
+                ),
+            }
+        }
+    }
+
+    impl Error for BallotError {}
+
+    /// Tokens a ballot costs. Negative votes cost the same as positive ones.
+    /// `None` if the cost does not fit in an i64, which is over any budget.
+    pub fn tokens_used(b: &Ballot) -> Option<i64> {
+        b.iter().try_fold(0, |acc: i64, (_, votes)| {
+            acc.checked_add(votes.checked_mul(*votes)?)
+        })
     }
 
     #[test]
     fn testtokensused() {
         struct Case {
             input: Ballot,
-            expected: i64,
+            expected: Option<i64>,
         }
 
         let cases = [
             Case {
                 input: BTreeMap::from([(0, 10), (1, 2)]),
-                expected: 104,
+                expected: Some(104),
             },
             Case {
                 input: BTreeMap::from([(0, 10)]),
-                expected: 100,
+                expected: Some(100),
             },
             Case {
                 input: BTreeMap::from([]),
-                expected: 0,
+                expected: Some(0),
             },
             Case {
                 input: BTreeMap::from([(0, 10), (1, -2)]),
-                expected: 104,
+                expected: Some(104),
+            },
+            Case {
+                input: BTreeMap::from([(0, i64::MIN), (1, 1)]),
+                expected: None,
+            },
+            Case {
+                input: BTreeMap::from([(0, 4_000_000_000)]),
+                expected: None,
+            },
+            Case {
+                input: BTreeMap::from([(0, 3_037_000_499), (1, 3_037_000_499)]),
+                expected: None,
             },
         ];
 
@@ -46,114 +206,273 @@ pub mod qv {
         }
     }
 
-    pub fn valid_ballot(b: &Ballot, max_tokens: i64, num_choices: i64) -> bool {
-        if tokens_used(b) > max_tokens {
-            return false;
+    /// Checks `b` against the session's `rules` for options `0..num_choices`.
+    pub fn check_ballot(b: &Ballot, rules: &Rules, num_choices: u32) -> Result<(), BallotError> {
+        for (choice, votes) in b.iter() {
+            let (choice, votes) = (*choice, *votes);
+            if choice < 0 {
+                return Err(BallotError::NegativeOption(choice));
+            }
+            if choice >= i64::from(num_choices) {
+                return Err(BallotError::UnknownOption(choice));
+            }
+            if votes < 0 && !rules.negative_votes {
+                return Err(BallotError::NegativeVotes { choice, votes });
+            }
+            if let Some(max_votes) = rules.max_votes
+                && votes.unsigned_abs() > max_votes.unsigned_abs()
+            {
+                return Err(BallotError::OverCap {
+                    choice,
+                    votes,
+                    max_votes,
+                });
+            }
+        }
+
+        // A cost too large to count is over any budget, however large.
+        let tokens = tokens_used(b);
+        if tokens.is_none_or(|tokens| tokens > rules.tokens_per_ballot) {
+            return Err(BallotError::OverBudget {
+                tokens,
+                budget: rules.tokens_per_ballot,
+            });
         }
+        return Ok(());
+    }
 
-        for (choice, _) in b.iter() {
-            if choice > &(num_choices - 1) {
-                return false;
+    /// The option, or options tied, with the most net votes.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Winner {
+        Single(vm::OptionId),
+        Tie(Vec<vm::OptionId>),
+    }
+
+    impl Winner {
+        pub fn options(&self) -> Vec<vm::OptionId> {
+            match self {
+                Winner::Single(winner) => vec![*winner],
+                Winner::Tie(tied) => tied.clone(),
             }
         }
+    }
 
-        return true;
+    // Options `0..num_choices` by descending net votes. Options nobody voted
+    // on have 0.
+    fn ranking(num_choices: u32, votes: &Ballot) -> Vec<Vec<vm::OptionId>> {
+        return vm::ranking_by_score(
+            (0..num_choices)
+                .map(|choice| (choice, votes.get(&i64::from(choice)).copied().unwrap_or(0))),
+        );
     }
 
-    fn winner(b: &Ballot) -> BallotChoice {
-        b.iter()
-            .fold((0, 0), |(winner, winner_votes), (choice, choice_votes)| {
-                // TODO: How to handle tie?
-                if choice_votes > &winner_votes {
-                    (*choice, *choice_votes)
-                } else {
-                    (winner, winner_votes)
-                }
-            })
-            .0
+    fn winner(ranking: &[Vec<vm::OptionId>]) -> Option<Winner> {
+        match ranking.first().map(Vec::as_slice) {
+            None | Some([]) => None,
+            Some([winner]) => Some(Winner::Single(*winner)),
+            Some(tied) => Some(Winner::Tie(tied.to_vec())),
+        }
     }
 
     #[test]
     fn testvalidballot() {
         struct Case {
             b: Ballot,
-            max_tokens: i64,
-            num_choices: i64,
-            expected: bool,
+            rules: Rules,
+            num_choices: u32,
+            expected: Result<(), BallotError>,
         }
 
+        let capped = Rules {
+            negative_votes: false,
+            max_votes: Some(3),
+            ..Rules::new(104)
+        };
         let cases = [
+            // Costs that overflow are over even the largest budget.
+            Case {
+                b: BTreeMap::from([(0, i64::MIN)]),
+                rules: Rules::new(i64::MAX),
+                num_choices: 1,
+                expected: Err(BallotError::OverBudget {
+                    tokens: None,
+                    budget: i64::MAX,
+                }),
+            },
+            Case {
+                b: BTreeMap::from([(0, 3_037_000_499), (1, 3_037_000_499)]),
+                rules: Rules::new(i64::MAX),
+                num_choices: 2,
+                expected: Err(BallotError::OverBudget {
+                    tokens: None,
+                    budget: i64::MAX,
+                }),
+            },
+            Case {
+                b: BTreeMap::from([(0, 3_037_000_499)]),
+                rules: Rules::new(i64::MAX),
+                num_choices: 1,
+                expected: Ok(()),
+            },
             Case {
                 b: BTreeMap::from([(0, 10), (1, 2)]),
-                max_tokens: 100,
+                rules: Rules::new(100),
                 num_choices: 2,
-                expected: false,
+                expected: Err(BallotError::OverBudget {
+                    tokens: Some(104),
+                    budget: 100,
+                }),
             },
             Case {
                 b: BTreeMap::from([(0, 10), (1, 2)]),
-                max_tokens: 104,
+                rules: Rules::new(104),
                 num_choices: 1,
-                expected: false,
+                expected: Err(BallotError::UnknownOption(1)),
             },
             Case {
                 b: BTreeMap::from([]),
-                max_tokens: 104,
+                rules: Rules::new(104),
                 num_choices: 1,
-                expected: true,
+                expected: Ok(()),
             },
             Case {
                 b: BTreeMap::from([(0, 10), (1, 1)]),
-                max_tokens: 104,
+                rules: Rules::new(104),
+                num_choices: 3,
+                expected: Ok(()),
+            },
+            Case {
+                b: BTreeMap::from([(-1, 1)]),
+                rules: Rules::new(104),
+                num_choices: 3,
+                expected: Err(BallotError::NegativeOption(-1)),
+            },
+            Case {
+                b: BTreeMap::from([(0, -2)]),
+                rules: Rules::new(104),
+                num_choices: 3,
+                expected: Ok(()),
+            },
+            Case {
+                b: BTreeMap::from([(0, 1), (2, -2)]),
+                rules: capped,
+                num_choices: 3,
+                expected: Err(BallotError::NegativeVotes {
+                    choice: 2,
+                    votes: -2,
+                }),
+            },
+            Case {
+                b: BTreeMap::from([(0, 3), (1, 4)]),
+                rules: capped,
                 num_choices: 3,
-                expected: true,
+                expected: Err(BallotError::OverCap {
+                    choice: 1,
+                    votes: 4,
+                    max_votes: 3,
+                }),
+            },
+            Case {
+                b: BTreeMap::from([(0, 3), (1, 3)]),
+                rules: capped,
+                num_choices: 3,
+                expected: Ok(()),
             },
         ];
 
         for case in cases.iter() {
             assert_eq!(
-                valid_ballot(&case.b, case.max_tokens, case.num_choices),
+                check_ballot(&case.b, &case.rules, case.num_choices),
                 case.expected
             );
         }
     }
 
+    #[test]
+    fn test_banking() {
+        let rules = Rules {
+            banking: true,
+            ..Rules::new(10)
+        };
+        assert_eq!(rules.with_credits(4).tokens_per_ballot, 14);
+        assert_eq!(rules.banked(4), 4);
+        assert_eq!(rules.banked(14), 10);
+        assert_eq!(rules.banked(-1), 0);
+        assert_eq!(Rules::new(10).banked(4), 0);
+
+        let banked = BankedBallot {
+            credits: 4,
+            ballot: BTreeMap::from([(0, 3), (1, 2)]),
+        };
+        assert_eq!(tokens_left(&banked, &rules), 1);
+        assert_eq!(
+            check_ballot(&banked.ballot, &rules.with_credits(banked.credits), 2),
+            Ok(())
+        );
+        assert_eq!(
+            check_ballot(&banked.ballot, &rules, 2),
+            Err(BallotError::OverBudget {
+                tokens: Some(13),
+                budget: 10
+            })
+        );
+    }
+
+    /// Tokens `b` leaves unspent of its voter's budget and banked credits.
+    pub fn tokens_left(b: &BankedBallot, rules: &Rules) -> i64 {
+        return rules
+            .with_credits(b.credits)
+            .tokens_per_ballot
+            .saturating_sub(tokens_used(&b.ballot).unwrap_or(i64::MAX));
+    }
+
     #[test]
     fn testwinner() {
         struct Case {
             b: Ballot,
-            expected: BallotChoice,
+            expected: Option<Winner>,
         }
 
         let cases = [
             Case {
                 b: BTreeMap::from([(0, 10), (1, 2)]),
-                expected: 0,
+                expected: Some(Winner::Single(0)),
             },
             Case {
                 b: BTreeMap::from([(0, 10), (1, 11)]),
-                expected: 1,
+                expected: Some(Winner::Single(1)),
             },
             Case {
                 b: BTreeMap::from([(3, 1)]),
-                expected: 3,
+                expected: Some(Winner::Single(3)),
+            },
+            Case {
+                b: BTreeMap::from([(1, 4), (2, 4), (3, -1)]),
+                expected: Some(Winner::Tie(vec![1, 2])),
+            },
+            // Every option nets 0, which ties them all.
+            Case {
+                b: BTreeMap::from([]),
+                expected: Some(Winner::Tie(vec![0, 1, 2, 3])),
             },
-            // TODO: Tie?
-            // TODO: Empty ballot?
         ];
 
         for case in cases.iter() {
-            assert_eq!(winner(&case.b), case.expected);
+            assert_eq!(winner(&ranking(4, &case.b)), case.expected);
         }
+        assert_eq!(winner(&ranking(0, &BTreeMap::new())), None);
     }
 
-    fn aggregate_ballots(ballots: Vec<&Ballot>) -> Ballot {
+    // Net votes by option, or `None` if a total does not fit in an i64.
+    fn aggregate_ballots(ballots: Vec<&Ballot>) -> Option<Ballot> {
         ballots
             .into_iter()
-            .fold(Ballot::new(), |mut result, ballot| {
+            .try_fold(Ballot::new(), |mut result, ballot| {
                 for (choice, ballot_votes) in ballot.iter() {
-                    *result.entry(*choice).or_insert(0) += ballot_votes;
+                    let votes = result.entry(*choice).or_insert(0);
+                    *votes = votes.checked_add(*ballot_votes)?;
                 }
-                return result;
+                return Some(result);
             })
     }
 
@@ -199,66 +518,175 @@ pub mod qv {
         for case in cases.iter() {
             assert_eq!(
                 aggregate_ballots(case.ballots.iter().collect()),
-                case.expected
+                Some(case.expected.clone())
             );
         }
     }
 
-    #[derive(Debug, PartialEq)]
+    /// Votes cast for and against one option over every ballot. Both are
+    /// counted as positive numbers.
+    #[derive(Debug, Clone, Copy, PartialEq, Default)]
+    pub struct OptionVotes {
+        pub positive: Votes,
+        pub negative: Votes,
+    }
+
+    impl OptionVotes {
+        pub fn net(&self) -> Votes {
+            return self.positive - self.negative;
+        }
+    }
+
+    #[test]
+    fn test_option_votes() {
+        let ballots = [
+            BTreeMap::from([(0, 3), (1, -2)]),
+            BTreeMap::from([(0, -1), (2, 4)]),
+        ];
+        let split = option_votes(&ballots.iter().collect::<Vec<&Ballot>>()).unwrap();
+        assert_eq!(
+            split,
+            BTreeMap::from([
+                (
+                    0,
+                    OptionVotes {
+                        positive: 3,
+                        negative: 1
+                    }
+                ),
+                (
+                    1,
+                    OptionVotes {
+                        positive: 0,
+                        negative: 2
+                    }
+                ),
+                (
+                    2,
+                    OptionVotes {
+                        positive: 4,
+                        negative: 0
+                    }
+                ),
+            ])
+        );
+        assert_eq!(split[&0].net(), 2);
+
+        let ballots = [BTreeMap::from([(0, i64::MIN)])];
+        assert_eq!(
+            option_votes(&ballots.iter().collect::<Vec<&Ballot>>()),
+            None
+        );
+    }
+
+    // Votes for and against each option, or `None` if a total does not fit
+    // in an i64.
+    fn option_votes(ballots: &[&Ballot]) -> Option<BTreeMap<BallotChoice, OptionVotes>> {
+        let mut split: BTreeMap<BallotChoice, OptionVotes> = BTreeMap::new();
+        for (choice, votes) in ballots.iter().flat_map(|ballot| ballot.iter()) {
+            let option = split.entry(*choice).or_default();
+            match *votes >= 0 {
+                true => option.positive = option.positive.checked_add(*votes)?,
+                false => option.negative = option.negative.checked_sub(*votes)?,
+            }
+        }
+        return Some(split);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
     pub struct VoteReport {
         pub num_voters: i64,
+        /// Every voter's budget plus the credits they banked.
         pub total_tokens_available: i64,
         pub total_tokens_remaining: i64,
+        /// Tokens each ballot left unspent, in ballot order.
+        pub tokens_remaining: Vec<i64>,
+        /// Net votes by option.
         pub votes: Ballot,
-        pub winner: BallotChoice,
+        /// Votes for and against each option that got any.
+        pub option_votes: BTreeMap<BallotChoice, OptionVotes>,
+        /// Every option by descending net votes.
+        pub ranking: Vec<Vec<vm::OptionId>>,
+        /// `None` if no ballot cast a vote.
+        pub winner: Option<Winner>,
     }
 
-    // TODO: Better error...
-    #[derive(Debug)]
-    pub struct VoteError {
-        details: String,
-    }
-
-    impl VoteError {
-        fn new(msg: &str) -> VoteError {
-            VoteError {
-                details: msg.to_string(),
-            }
-        }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum VoteError {
+        /// Ballot `ballot` (numbered from 0) breaks the session's rules.
+        InvalidBallot { ballot: usize, error: BallotError },
+        /// The token or vote totals do not fit in an i64.
+        TooManyVotes,
     }
 
     impl fmt::Display for VoteError {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "{}", self.details)
+            match self {
+                VoteError::InvalidBallot { ballot, error } => {
+                    write!(f, "Ballot {ballot} is invalid: {error}")
+                }
+                VoteError::TooManyVotes => write!(f, "Too many votes to count"),
+            }
         }
     }
 
     impl Error for VoteError {
-        fn description(&self) -> &str {
-            &self.details
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                VoteError::InvalidBallot { error, .. } => Some(error),
+                VoteError::TooManyVotes => None,
+            }
         }
     }
 
-    pub fn vote(ballots: Vec<&Ballot>, tokens_per_ballot: i64) -> Result<VoteReport, VoteError> {
-        for ballot in ballots.iter() {
-            if !valid_ballot(ballot, tokens_per_ballot, std::i64::MAX) {
-                // TODO: Better error
-                return Err(VoteError::new(&format!("invalid ballot: {:#?}", ballot)));
-            }
+    pub fn vote(
+        num_choices: u32,
+        banked_ballots: Vec<&BankedBallot>,
+        rules: &Rules,
+    ) -> Result<VoteReport, VoteError> {
+        for (i, banked) in banked_ballots.iter().enumerate() {
+            check_ballot(
+                &banked.ballot,
+                &rules.with_credits(banked.credits),
+                num_choices,
+            )
+            .map_err(|error| VoteError::InvalidBallot { ballot: i, error })?;
         }
+        let ballots: Vec<&Ballot> = banked_ballots.iter().map(|banked| &banked.ballot).collect();
         // TODO: Is there a way to avoid this clone?
-        let final_ballot = aggregate_ballots(ballots.clone());
-        let tokens_spent = tokens_used(&final_ballot);
+        let final_ballot = aggregate_ballots(ballots.clone()).ok_or(VoteError::TooManyVotes)?;
 
-        let num_voters: i64 = ballots.len().try_into().unwrap();
-        // .map_err(|err| VoteError::new(format!("getting number of ballots: {}", err)))?;
-        let total_tokens_available = num_voters * tokens_per_ballot;
-        let total_tokens_remaining = total_tokens_available - tokens_spent;
+        let num_voters = i64::try_from(ballots.len()).map_err(|_| VoteError::TooManyVotes)?;
+        let total_tokens_available = banked_ballots
+            .iter()
+            .try_fold(0, |total: i64, banked| {
+                total.checked_add(rules.tokens_per_ballot.checked_add(banked.credits)?)
+            })
+            .ok_or(VoteError::TooManyVotes)?;
+        let tokens_remaining: Vec<i64> = banked_ballots
+            .iter()
+            .map(|banked| tokens_left(banked, rules))
+            .collect();
+        let total_tokens_remaining = tokens_remaining
+            .iter()
+            .try_fold(0, |total: i64, left| total.checked_add(*left))
+            .ok_or(VoteError::TooManyVotes)?;
+        let ranking = ranking(num_choices, &final_ballot);
         return Ok(VoteReport {
             num_voters,
             total_tokens_available,
             total_tokens_remaining,
-            winner: winner(&final_ballot),
+            tokens_remaining,
+            option_votes: option_votes(&ballots).ok_or(VoteError::TooManyVotes)?,
+            winner: match ballots
+                .iter()
+                .flat_map(|ballot| ballot.values())
+                .any(|votes| *votes != 0)
+            {
+                true => winner(&ranking),
+                false => None,
+            },
+            ranking,
             votes: final_ballot,
         });
     }
@@ -267,22 +695,38 @@ pub mod qv {
     fn testvote() {
         struct Case {
             ballots: Vec<Ballot>,
-            expected_winner: BallotChoice,
+            expected_winner: Option<Winner>,
         }
 
         let cases = [
             Case {
                 ballots: Vec::from([BTreeMap::from([(0, 10), (1, 2)])]),
-                expected_winner: 0,
+                expected_winner: Some(Winner::Single(0)),
             },
             Case {
                 ballots: Vec::from([BTreeMap::from([(1, 2)])]),
-                expected_winner: 1,
+                expected_winner: Some(Winner::Single(1)),
             },
             Case {
-                // TODO: this behavior is kind of undefined
                 ballots: Vec::from([BTreeMap::from([])]),
-                expected_winner: 0,
+                expected_winner: None,
+            },
+            Case {
+                ballots: Vec::from([]),
+                expected_winner: None,
+            },
+            Case {
+                ballots: Vec::from([BTreeMap::from([(0, 0)])]),
+                expected_winner: None,
+            },
+            Case {
+                ballots: Vec::from([BTreeMap::from([(0, 3)]), BTreeMap::from([(2, 3)])]),
+                expected_winner: Some(Winner::Tie(vec![0, 2])),
+            },
+            // Votes were cast, they just cancel out.
+            Case {
+                ballots: Vec::from([BTreeMap::from([(0, 3)]), BTreeMap::from([(0, -3)])]),
+                expected_winner: Some(Winner::Tie(vec![0, 1, 2, 3])),
             },
             Case {
                 ballots: Vec::from([
@@ -291,7 +735,7 @@ pub mod qv {
                     BTreeMap::from([(3, 4)]),
                     BTreeMap::from([(1, 3)]),
                 ]),
-                expected_winner: 1,
+                expected_winner: Some(Winner::Single(1)),
             },
             Case {
                 ballots: Vec::from([
@@ -299,71 +743,206 @@ pub mod qv {
                     BTreeMap::from([(0, 11), (1, 1), (2, 3)]),
                     BTreeMap::from([(0, 0), (1, 13), (3, 7)]),
                 ]),
-                expected_winner: 1,
+                expected_winner: Some(Winner::Single(1)),
             },
         ];
 
         for case in cases.iter() {
-            match vote(case.ballots.iter().collect(), 1004) {
+            let ballots: Vec<BankedBallot> = case
+                .ballots
+                .iter()
+                .cloned()
+                .map(BankedBallot::from)
+                .collect();
+            match vote(4, ballots.iter().collect(), &Rules::new(1004)) {
                 Ok(result) => assert_eq!(result.winner, case.expected_winner),
-                Err(err) => assert!(false, "{}", err),
+                Err(err) => panic!("{}", err),
             };
         }
+
+        // Banked credits count towards the budget and the tokens left.
+        let banked = [
+            BankedBallot::from(BTreeMap::from([(1, 2)])),
+            BankedBallot {
+                credits: 5,
+                ballot: BTreeMap::from([(0, 3), (1, -1)]),
+            },
+        ];
+        let report = vote(4, banked.iter().collect(), &Rules::new(8)).unwrap();
+        assert_eq!(report.tokens_remaining, vec![4, 3]);
+        assert_eq!(report.total_tokens_remaining, 7);
+        assert_eq!(report.total_tokens_available, 21);
+
+        let over = BankedBallot::from(BTreeMap::from([(0, 30)]));
+        let under = BankedBallot::from(BTreeMap::from([(1, 2)]));
+        assert_eq!(
+            vote(4, vec![&under, &over], &Rules::new(100)),
+            Err(VoteError::InvalidBallot {
+                ballot: 1,
+                error: BallotError::OverBudget {
+                    tokens: Some(900),
+                    budget: 100
+                }
+            })
+        );
+        // A cost that overflows is refused, not counted. `vote` checks
+        // against the budget with credits, which is at most `MAX_TOKENS`.
+        let huge = BankedBallot::from(BTreeMap::from([(0, i64::MIN)]));
+        assert_eq!(
+            vote(4, vec![&huge], &Rules::new(i64::MAX)),
+            Err(VoteError::InvalidBallot {
+                ballot: 0,
+                error: BallotError::OverBudget {
+                    tokens: None,
+                    budget: MAX_TOKENS
+                }
+            })
+        );
+        assert_eq!(
+            vote(
+                4,
+                vec![&BankedBallot::from(BTreeMap::from([(4, 2)]))],
+                &Rules::new(100)
+            ),
+            Err(VoteError::InvalidBallot {
+                ballot: 0,
+                error: BallotError::UnknownOption(4)
+            })
+        );
     }
-}
 
-// fn parse_qv_ballot(ballot_str: String) -> Result<qv::Ballot, ParseError> {
-//     ballot_str
-//         .trim()
-//         .split(",")
-//         .try_fold(BTreeMap::new(), |mut map, vote_raw| {
-//             let split = vote_raw.split(":").collect::<Vec<&str>>();
-//             if split.len() != 2 {
-//                 return Err(ParseError::new(&format!(
-//                     "Vote '{vote_raw}' is an invalid format"
-//                 )));
-//             }
-//             let ballot_key = match split[0].trim().parse::<i64>() {
-//                 Ok(x) => x,
-//                 Err(err) => {
-//                     return Err(ParseError::new(&format!(
-//                         "Failed to parse first part of '{vote_raw}' as an i64: {err}"
-//                     )))
-//                 }
-//             };
-//             let ballot_votes = match split[1].trim().parse::<i64>() {
-//                 Ok(x) => x,
-//                 Err(err) => {
-//                     return Err(ParseError::new(&format!(
-//                         "Failed to parse second part of '{vote_raw}' as an i64: {err}"
-//                     )))
-//                 }
-//             };
-
-//             if map.contains_key(&ballot_key) {
-//                 return Err(ParseError::new(&format!(
-//                     "Invalid vote '{vote_raw}'; cannot vote twice for the same candidate"
-//                 )));
-//             }
-
-//             map.insert(ballot_key, ballot_votes);
-//             Ok(map)
-//         })
-// }
-
-// #[test]
-// fn test_parse_qv_ballot() {
-//     struct Case {
-//         input: String,
-//         expected: qv::Ballot,
-//     }
-
-//     let cases = [Case {
-//         input: "1: 3, 4: 6, 9: 100".to_string(),
-//         expected: qv::Ballot::from([(1, 3), (4, 6), (9, 100)]),
-//     }];
-
-//     for case in cases.iter() {
-//         assert_eq!(parse_ballot(case.input.clone()).unwrap(), case.expected,);
-//     }
-// }
+    /// Quadratic voting under a session's `rules`.
+    pub struct QuadraticVoting {
+        pub rules: Rules,
+    }
+
+    impl vm::VotingMethod for QuadraticVoting {
+        type Ballot = BankedBallot;
+        type Details = VoteReport;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            "Quadratic"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<BankedBallot>,
+        ) -> Result<vm::Outcome<VoteReport>, VoteError> {
+            let report = vote(num_choices, ballots.iter().collect(), &self.rules)?;
+            return Ok(vm::Outcome {
+                winners: report
+                    .winner
+                    .as_ref()
+                    .map(Winner::options)
+                    .unwrap_or_default(),
+                ranking: report.ranking.clone(),
+                details: report,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteReport>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let report = &outcome.details;
+            let name = |choice: vm::OptionId| vm::option_name(choice, option_id_to_option);
+            let mut message = match &report.winner {
+                Some(Winner::Single(winner)) => {
+                    format!("Quadratic vote winner: {winner} - **{}**.\n", name(*winner))
+                }
+                Some(Winner::Tie(tied)) => format!(
+                    "Quadratic vote tied between {}.\n",
+                    tied.iter()
+                        .map(|choice| format!("**{}**", name(*choice)))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                None => "No quadratic votes were cast.\n".to_string(),
+            };
+            // Most net votes first, each with the votes for and against it.
+            message += "Votes per option:\n";
+            for id in report.ranking.iter().flatten() {
+                let split = report
+                    .option_votes
+                    .get(&i64::from(*id))
+                    .copied()
+                    .unwrap_or_default();
+                message += &format!(
+                    "- {id}: {} - {} (+{} / -{})\n",
+                    name(*id),
+                    split.net(),
+                    split.positive,
+                    split.negative
+                );
+            }
+            message += &format!(
+                "Tokens remaining: {} of {} ({} voters)\nRules: {}",
+                report.total_tokens_remaining,
+                report.total_tokens_available,
+                report.num_voters,
+                self.rules,
+            );
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = Vec::from([
+            BTreeMap::from([(0, 2), (1, 4), (2, 8)]),
+            BTreeMap::from([(0, 11), (1, 1), (2, 3)]),
+            BTreeMap::from([(0, 0), (1, 13), (3, 7)]),
+        ]);
+        let outcome = QuadraticVoting {
+            rules: Rules::new(1004),
+        }
+        .tally(5, ballots.into_iter().map(BankedBallot::from).collect())
+        .unwrap();
+        assert_eq!(outcome.winners, vec![1]);
+        assert_eq!(
+            outcome.ranking,
+            vec![vec![1], vec![0], vec![2], vec![3], vec![4]]
+        );
+
+        let method = QuadraticVoting {
+            rules: Rules {
+                max_votes: Some(3),
+                ..Rules::new(11)
+            },
+        };
+        let ballots = Vec::from([
+            BTreeMap::from([(0, 2), (2, -1)]),
+            BTreeMap::from([(1, 3), (0, 1), (2, 1)]),
+        ]);
+        let outcome = method
+            .tally(3, ballots.into_iter().map(BankedBallot::from).collect())
+            .unwrap();
+        assert_eq!(outcome.winners, vec![0, 1]);
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            method.describe(&outcome, &options),
+            "Quadratic vote tied between **Dune**, **Emma**.
+Votes per option:
+- 0: Dune - 3 (+3 / -0)
+- 1: Emma - 3 (+3 / -0)
+- 2: 2 - 0 (+1 / -1)
+Tokens remaining: 6 of 22 (2 voters)
+Rules: 11 tokens per voter, at most 3 votes per option"
+        );
+
+        let outcome = method
+            .tally(3, vec![BankedBallot::from(Ballot::new())])
+            .unwrap();
+        assert_eq!(outcome.winners, Vec::<vm::OptionId>::new());
+        assert!(
+            method
+                .describe(&outcome, &options)
+                .starts_with("No quadratic votes were cast.")
+        );
+    }
+}
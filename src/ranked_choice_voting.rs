@@ -1,34 +1,35 @@
 pub mod rcv {
-    pub type BallotChoice = i64;
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::fmt;
+
+    pub type BallotChoice = u32;
     pub type Ballot = Vec<BallotChoice>;
 
+    #[derive(Debug, Clone, PartialEq)]
     pub struct VoteBreakdown {
-        s: String,
-    }
-
-    // TODO: Better error...
-    #[derive(Debug)]
-    pub struct VoteError {
-        details: String,
+        pub winner: Option<BallotChoice>,
+        pub rounds: Vec<RoundBreakdown>,
     }
 
-    impl VoteError {
-        fn new(msg: &str) -> VoteError {
-            VoteError {
-                details: msg.to_string(),
+    impl std::fmt::Display for VoteBreakdown {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> std::fmt::Result {
+            match self.winner {
+                Some(winner) => fmt.write_str(&format!("Winner - {winner}\n"))?,
+                None => fmt.write_str("No winner!\n")?,
+            };
+            for (i, round) in self.rounds.iter().enumerate() {
+                fmt.write_str(&format!(
+                    "Round {}: votes {:?}, eliminated {:?}, transfers {:?}, exhausted {}\n",
+                    i + 1,
+                    round.votes_by_candidate,
+                    round.eliminated,
+                    round.transfers,
+                    round.exhausted,
+                ))?;
             }
-        }
-    }
-
-    impl fmt::Display for VoteError {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "{}", self.details)
-        }
-    }
-
-    impl Error for VoteError {
-        fn description(&self) -> &str {
-            &self.details
+            Ok(())
         }
     }
 
@@ -69,6 +70,16 @@ pub mod rcv {
                 ballots: Vec::from([Ballot::from([2, 1, 3])]),
                 expected: Some(2),
             },
+            Case {
+                // Exhausted ballots don't count towards the majority
+                ballots: Vec::from([
+                    Ballot::from([2]),
+                    Ballot::from([2]),
+                    Ballot::from([1]),
+                    Ballot::from([]),
+                ]),
+                expected: Some(2),
+            },
             Case {
                 ballots: Vec::from([]),
                 expected: None,
@@ -76,25 +87,73 @@ pub mod rcv {
         ];
 
         for case in cases.iter() {
-            assert_eq!(round_winner(case.ballots, case.round), case.expected,)
+            assert_eq!(round_winner(&case.ballots), case.expected,)
         }
     }
 
+    /// One counting round. `votes_by_candidate` holds the first preferences of
+    /// every continuing candidate at the start of the round; `transfers` shows
+    /// where the ballots of the `eliminated` candidate went next.
+    #[derive(Debug, Clone, PartialEq)]
     pub struct RoundBreakdown {
-        starting_ballots: Vec<Ballot>,
-        votes_by_candidate: BTreeMap<BallotChoice, i64>,
-        eliminated: Option<BallotChoice>,
-        ending_ballots: Vec<Ballot>,
-        winner: Option<BallotChoice>,
+        pub votes_by_candidate: BTreeMap<BallotChoice, u64>,
+        pub eliminated: Option<BallotChoice>,
+        pub transfers: BTreeMap<BallotChoice, u64>,
+        pub exhausted: u64,
+        pub winner: Option<BallotChoice>,
+    }
+
+    fn first_preferences(
+        ballots: &[Ballot],
+        continuing: &BTreeSet<BallotChoice>,
+    ) -> BTreeMap<BallotChoice, u64> {
+        let mut votes_by_candidate: BTreeMap<BallotChoice, u64> =
+            continuing.iter().map(|choice| (*choice, 0)).collect();
+        for ballot in ballots.iter() {
+            if let Some(choice) = ballot.first() {
+                *votes_by_candidate.entry(*choice).or_insert(0) += 1;
+            }
+        }
+        return votes_by_candidate;
+    }
+
+    // A candidate wins a round with a strict majority of the ballots that are
+    // not yet exhausted.
+    fn round_winner(ballots: &[Ballot]) -> Option<BallotChoice> {
+        let active_ballots = ballots.iter().filter(|ballot| !ballot.is_empty()).count();
+        let winner_requirement = vm::count(active_ballots / 2 + 1);
+
+        let votes_by_candidate = first_preferences(ballots, &BTreeSet::new());
+        for (choice, votes) in votes_by_candidate.iter() {
+            if *votes >= winner_requirement {
+                return Some(*choice);
+            }
+        }
+        return None;
+    }
+
+    #[test]
+    fn test_eliminate_option() {
+        assert_eq!(
+            eliminate_option(
+                vec![
+                    Ballot::from([3, 1, 2]),
+                    Ballot::from([1, 3, 2]),
+                    Ballot::from([1]),
+                ],
+                1
+            ),
+            vec![Ballot::from([3, 2]), Ballot::from([3, 2]), Ballot::from([])],
+        );
     }
 
     fn eliminate_option(ballots: Vec<Ballot>, eliminated: BallotChoice) -> Vec<Ballot> {
         ballots
-            .iter()
+            .into_iter()
             .map(|ballot| {
                 ballot
                     .into_iter()
-                    .filter(|choice| choice != eliminated)
+                    .filter(|choice| *choice != eliminated)
                     .collect()
             })
             .collect()
@@ -117,75 +176,385 @@ pub mod rcv {
                 expected: 2,
             },
             Case {
+                // 1 and 4 both have no first preferences, but 1 has three
+                // second preferences to 4's none.
                 ballots: Vec::from([
                     Ballot::from([3, 1, 2, 4]),
                     Ballot::from([2, 3, 1, 4]),
                     Ballot::from([3, 1, 2, 4]),
                     Ballot::from([2, 1, 3, 4]),
                 ]),
+                expected: 4,
+            },
+            Case {
+                // Tied at every preference level, the highest option ID goes.
+                ballots: Vec::from([Ballot::from([1, 2]), Ballot::from([2, 1])]),
                 expected: 2,
             },
         ];
 
         for case in cases.iter() {
-            assert_eq!(pick_eliminate(case.ballots), expected);
+            let continuing: BTreeSet<BallotChoice> =
+                case.ballots.iter().flatten().copied().collect();
+            assert_eq!(pick_eliminate(&case.ballots, &continuing), case.expected);
         }
     }
 
-    fn pick_eliminate(ballots: Vec<Ballot>) -> BallotChoice {}
+    /// Picks the continuing candidate with the fewest first preferences.
+    ///
+    /// Ties are broken by comparing second preferences, then third
+    /// preferences and so on; the candidate with fewer votes at the first
+    /// level where the tied candidates differ is eliminated. If they are tied
+    /// at every level, the candidate with the highest option ID (the most
+    /// recent suggestion) is eliminated.
+    fn pick_eliminate(ballots: &[Ballot], continuing: &BTreeSet<BallotChoice>) -> BallotChoice {
+        let num_levels = ballots.iter().map(|ballot| ballot.len()).max().unwrap_or(0);
+        let mut tied: Vec<BallotChoice> = continuing.iter().copied().collect();
 
-    fn run_round(ballots: Vec<Ballot>) -> RoundBreakdown {
-        let winner_requirement = (ballots.len() / 2) + 1;
-
-        let mut votes_by_candidate = BTreeMap::new();
-        for ballot in ballots.iter() {
-            if let Some(choice) = ballot.get(0) {
-                match votes_by_candidate.get(choice) {
-                    Some(votes) => {
-                        votes_by_candidate.insert(choice, votes + 1);
-                    }
-                    None => {
-                        votes_by_candidate.insert(choice, 1);
-                    }
+        for level in 0..num_levels.max(1) {
+            let mut votes_at_level: BTreeMap<BallotChoice, u64> =
+                tied.iter().map(|choice| (*choice, 0)).collect();
+            for ballot in ballots.iter() {
+                if let Some(choice) = ballot.get(level)
+                    && let Some(votes) = votes_at_level.get_mut(choice)
+                {
+                    *votes += 1;
                 }
             }
+
+            let fewest = *votes_at_level.values().min().unwrap();
+            tied.retain(|choice| votes_at_level[choice] == fewest);
+            if tied.len() == 1 {
+                break;
+            }
         }
 
-        for (choice, votes) in &votes_by_candidate {
-            if votes >= winner_requirement {
-                return RoundBreakdown {
-                    starting_ballots: ballots,
+        return *tied.iter().max().unwrap();
+    }
+
+    fn run_round(
+        ballots: Vec<Ballot>,
+        continuing: &BTreeSet<BallotChoice>,
+    ) -> (RoundBreakdown, Vec<Ballot>) {
+        let votes_by_candidate = first_preferences(&ballots, continuing);
+
+        let winner = match round_winner(&ballots) {
+            Some(winner) => Some(winner),
+            None if continuing.len() == 1 => continuing.first().copied(),
+            None => None,
+        };
+        if winner.is_some() {
+            return (
+                RoundBreakdown {
                     votes_by_candidate,
                     eliminated: None,
-                    ending_ballots: ballots,
-                    winner: Some(choice),
-                };
-            }
+                    transfers: BTreeMap::new(),
+                    exhausted: 0,
+                    winner,
+                },
+                ballots,
+            );
         }
 
-        let eliminated = pick_eliminate(ballots);
-        let ending_ballots = eliminate_option(ballots, eliminated);
+        let eliminated = pick_eliminate(&ballots, continuing);
+        let ending_ballots = eliminate_option(ballots.clone(), eliminated);
 
-        return RoundBreakdown {
-            starting_ballots: ballots,
-            votes_by_candidate,
-            eliminated,
+        let mut transfers = BTreeMap::new();
+        let mut exhausted = 0;
+        for (before, after) in ballots.iter().zip(ending_ballots.iter()) {
+            if before.first() != Some(&eliminated) {
+                continue;
+            }
+            match after.first() {
+                Some(choice) => *transfers.entry(*choice).or_insert(0) += 1,
+                None => exhausted += 1,
+            }
+        }
+
+        return (
+            RoundBreakdown {
+                votes_by_candidate,
+                eliminated: Some(eliminated),
+                transfers,
+                exhausted,
+                winner: None,
+            },
             ending_ballots,
-            winner: None,
-        };
+        );
     }
 
-    pub fn vote(
-        num_choices: i64,
-        ballots: Vec<Ballot>,
-    ) -> Result<Tuple<BallotChoice, VoteBreakdown>, VoteError> {
+    #[test]
+    fn test_vote() {
+        struct Case {
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+            expected_winner: Option<BallotChoice>,
+            expected_eliminated: Vec<BallotChoice>,
+        }
+
+        let cases = [
+            Case {
+                num_choices: 3,
+                ballots: vec![
+                    Ballot::from([0, 1, 2]),
+                    Ballot::from([0, 2, 1]),
+                    Ballot::from([1, 2, 0]),
+                    Ballot::from([2, 1, 0]),
+                    Ballot::from([2, 1, 0]),
+                ],
+                expected_winner: Some(2),
+                expected_eliminated: vec![1],
+            },
+            Case {
+                num_choices: 4,
+                ballots: vec![
+                    Ballot::from([0, 1, 2, 3]),
+                    Ballot::from([0, 1, 2, 3]),
+                    Ballot::from([1, 0, 2, 3]),
+                    Ballot::from([1, 0, 2, 3]),
+                    Ballot::from([2, 1, 0, 3]),
+                ],
+                expected_winner: Some(1),
+                expected_eliminated: vec![3, 2],
+            },
+            Case {
+                num_choices: 2,
+                ballots: vec![Ballot::from([0, 1])],
+                expected_winner: Some(0),
+                expected_eliminated: vec![],
+            },
+            Case {
+                // Truncated ballots exhaust once their choices are eliminated
+                num_choices: 3,
+                ballots: vec![
+                    Ballot::from([0]),
+                    Ballot::from([0]),
+                    Ballot::from([1]),
+                    Ballot::from([1]),
+                    Ballot::from([2]),
+                ],
+                expected_winner: Some(0),
+                expected_eliminated: vec![2, 1],
+            },
+        ];
+
+        for case in cases.iter() {
+            let result = vote(case.num_choices, case.ballots.clone()).unwrap();
+            assert_eq!(result.winner, case.expected_winner);
+            let eliminated: Vec<BallotChoice> = result
+                .rounds
+                .iter()
+                .filter_map(|round| round.eliminated)
+                .collect();
+            assert_eq!(eliminated, case.expected_eliminated);
+        }
+
+        let result = vote(3, cases[0].ballots.clone()).unwrap();
+        assert_eq!(
+            result.rounds[0].votes_by_candidate,
+            BTreeMap::from([(0, 2), (1, 1), (2, 2)])
+        );
+        assert_eq!(result.rounds[0].transfers, BTreeMap::from([(2, 1)]));
+        assert_eq!(
+            result.rounds[1].votes_by_candidate,
+            BTreeMap::from([(0, 2), (2, 3)])
+        );
+
+        assert_eq!(vote(3, vec![]), Err(cv::VoteError::NoBallots));
+        assert_eq!(vote(0, vec![Ballot::new()]), Err(cv::VoteError::NoOptions));
+        assert_eq!(
+            vote(3, vec![Ballot::from([0]), Ballot::from([0, 1, 2, 0])]),
+            Err(cv::VoteError::BallotTooLong {
+                ballot: 1,
+                len: 4,
+                num_choices: 3
+            })
+        );
+        assert_eq!(
+            vote(3, vec![Ballot::from([0, 1, 1])]),
+            Err(cv::VoteError::DuplicateRank {
+                ballot: 0,
+                choice: 1
+            })
+        );
+        assert_eq!(
+            vote(3, vec![Ballot::from([0, 1, 3])]),
+            Err(cv::VoteError::UnknownOption {
+                ballot: 0,
+                choice: 3
+            })
+        );
+    }
+
+    pub fn vote(num_choices: u32, ballots: Vec<Ballot>) -> Result<VoteBreakdown, cv::VoteError> {
+        if ballots.is_empty() {
+            return Err(cv::VoteError::NoBallots);
+        }
+        if num_choices == 0 {
+            return Err(cv::VoteError::NoOptions);
+        }
+
         for (i, ballot) in ballots.iter().enumerate() {
-            if ballot.len() != num_choices {
-                return VoteError::new(format!(
-                    "Ballot {i} ({}) has an invalid number of choices",
-                    ballot
+            if ballot.len() > vm::index(num_choices) {
+                return Err(cv::VoteError::BallotTooLong {
+                    ballot: i,
+                    len: ballot.len(),
+                    num_choices,
+                });
+            }
+            let mut seen: BTreeSet<BallotChoice> = BTreeSet::new();
+            for choice in ballot.iter() {
+                if *choice >= num_choices {
+                    return Err(cv::VoteError::UnknownOption {
+                        ballot: i,
+                        choice: *choice,
+                    });
+                }
+                if !seen.insert(*choice) {
+                    return Err(cv::VoteError::DuplicateRank {
+                        ballot: i,
+                        choice: *choice,
+                    });
+                }
+            }
+        }
+
+        let mut continuing: BTreeSet<BallotChoice> = (0..num_choices).collect();
+        let mut ballots = ballots;
+        let mut rounds = Vec::new();
+        loop {
+            let (round, ending_ballots) = run_round(ballots, &continuing);
+            let winner = round.winner;
+            if let Some(eliminated) = round.eliminated {
+                continuing.remove(&eliminated);
+            }
+            rounds.push(round);
+
+            if winner.is_some() || continuing.is_empty() {
+                return Ok(VoteBreakdown { winner, rounds });
+            }
+            ballots = ending_ballots;
+        }
+    }
+
+    /// Instant-runoff voting over full ranked ballots.
+    pub struct InstantRunoff;
+
+    impl vm::VotingMethod for InstantRunoff {
+        type Ballot = Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Instant-runoff"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+
+            // The winner first, then the other candidates still standing in
+            // the final round by their votes, then everyone else in reverse
+            // order of elimination.
+            let mut ranking: Vec<Vec<vm::OptionId>> = breakdown
+                .winner
+                .iter()
+                .map(|winner| vec![*winner])
+                .collect();
+            if let Some(last_round) = breakdown.rounds.last() {
+                ranking.extend(vm::ranking_by_score(
+                    last_round
+                        .votes_by_candidate
+                        .iter()
+                        .filter(|(choice, _)| Some(**choice) != breakdown.winner)
+                        .map(|(choice, votes)| (*choice, *votes)),
                 ));
             }
+            for round in breakdown.rounds.iter().rev() {
+                if let Some(eliminated) = round.eliminated {
+                    ranking.push(vec![eliminated]);
+                }
+            }
+
+            return Ok(vm::Outcome {
+                winners: breakdown.winner.into_iter().collect(),
+                ranking,
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let mut message = match result.winner {
+                Some(winner) => format!(
+                    "Instant-runoff winner: {winner} - **{}**.\n",
+                    vm::option_name(winner, option_id_to_option),
+                ),
+                None => "No instant-runoff winner.\n".to_string(),
+            };
+
+            for (i, round) in result.rounds.iter().enumerate() {
+                let counts: Vec<String> = round
+                    .votes_by_candidate
+                    .iter()
+                    .map(|(choice, votes)| {
+                        format!("{} {votes}", vm::option_label(*choice, option_id_to_option))
+                    })
+                    .collect();
+                message += &format!("Round {}: {}.", i + 1, counts.join(", "));
+
+                if let Some(eliminated) = round.eliminated {
+                    message += &format!(
+                        " Eliminated {}",
+                        vm::option_label(eliminated, option_id_to_option)
+                    );
+                    let mut transfers: Vec<String> = round
+                        .transfers
+                        .iter()
+                        .map(|(choice, votes)| {
+                            format!(
+                                "+{votes} to {}",
+                                vm::option_label(*choice, option_id_to_option)
+                            )
+                        })
+                        .collect();
+                    if round.exhausted > 0 {
+                        transfers.push(format!("{} exhausted", round.exhausted));
+                    }
+                    if !transfers.is_empty() {
+                        message += &format!(" ({})", transfers.join(", "));
+                    }
+                    message += ".";
+                }
+                if let Some(winner) = round.winner {
+                    message += &format!(" {} wins.", vm::option_label(winner, option_id_to_option));
+                }
+                message += "\n";
+            }
+            return message;
         }
     }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            Ballot::from([0, 1, 2, 3]),
+            Ballot::from([0, 1, 2, 3]),
+            Ballot::from([1, 0, 2, 3]),
+            Ballot::from([1, 0, 2, 3]),
+            Ballot::from([2, 1, 0, 3]),
+        ];
+        let outcome = InstantRunoff.tally(4, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![1]);
+        assert_eq!(outcome.ranking, vec![vec![1], vec![0], vec![2], vec![3]]);
+    }
 }
@@ -0,0 +1,350 @@
+pub mod rp {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    /// A pairwise win of `winner` over `loser`, `votes_for` to `votes_against`.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Pair {
+        pub winner: cv::BallotChoice,
+        pub loser: cv::BallotChoice,
+        pub votes_for: u32,
+        pub votes_against: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        pub winner: Option<cv::BallotChoice>,
+        /// Every candidate no locked pair beats, before any tie-breaking.
+        pub tied_winners: Vec<cv::BallotChoice>,
+        /// Pairs locked in, strongest first.
+        pub locked: Vec<Pair>,
+        /// Pairs skipped because they would have created a cycle.
+        pub skipped: Vec<Pair>,
+        pub tie_breaker: cv::TieBreaker,
+        /// The tie-breaking order of all candidates, if a tie had to be broken.
+        pub tie_break_order: Option<Vec<cv::BallotChoice>>,
+        pub final_outranking: nalgebra::DMatrix<u32>,
+    }
+
+    #[test]
+    fn test_sorted_pairs() {
+        let m = nalgebra::DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0, 5, 2, //
+                2, 0, 5, //
+                3, 2, 0, //
+            ],
+        );
+        let pairs = sorted_pairs(&m, None);
+        let pair_ids: Vec<(cv::BallotChoice, cv::BallotChoice)> =
+            pairs.iter().map(|pair| (pair.winner, pair.loser)).collect();
+        assert_eq!(pair_ids, vec![(0, 1), (1, 2), (2, 0)]);
+
+        // The tie-breaking order decides between the two 5-2 wins.
+        let pairs = sorted_pairs(&m, Some(&[1, 0, 2]));
+        assert_eq!((pairs[0].winner, pairs[0].loser), (1, 2));
+    }
+
+    // Every pairwise win, strongest first: most votes for the winner, then
+    // fewest votes against. Equally strong pairs go in tie-breaking `order`
+    // of their winners (then reversed order of their losers), or by option
+    // ID without one.
+    fn sorted_pairs(
+        final_outranking: &nalgebra::DMatrix<u32>,
+        order: Option<&[cv::BallotChoice]>,
+    ) -> Vec<Pair> {
+        let position = |choice: cv::BallotChoice| match order {
+            Some(order) => order.iter().position(|c| *c == choice).unwrap(),
+            None => vm::index(choice),
+        };
+
+        let num_choices = final_outranking.nrows();
+        let mut pairs: Vec<Pair> = Vec::new();
+        for runner in 0..num_choices {
+            for opponent in 0..num_choices {
+                if final_outranking[(runner, opponent)] > final_outranking[(opponent, runner)] {
+                    pairs.push(Pair {
+                        winner: vm::option_id(runner),
+                        loser: vm::option_id(opponent),
+                        votes_for: final_outranking[(runner, opponent)],
+                        votes_against: final_outranking[(opponent, runner)],
+                    });
+                }
+            }
+        }
+
+        pairs.sort_by(|a, b| {
+            b.votes_for
+                .cmp(&a.votes_for)
+                .then(a.votes_against.cmp(&b.votes_against))
+                .then(position(a.winner).cmp(&position(b.winner)))
+                .then(position(b.loser).cmp(&position(a.loser)))
+        });
+        return pairs;
+    }
+
+    // Whether `to` can be reached from `from` by following locked pairs.
+    fn reaches(locked: &[Pair], from: cv::BallotChoice, to: cv::BallotChoice) -> bool {
+        let mut stack = vec![from];
+        let mut seen = vec![from];
+        while let Some(current) = stack.pop() {
+            if current == to {
+                return true;
+            }
+            for pair in locked.iter().filter(|pair| pair.winner == current) {
+                if !seen.contains(&pair.loser) {
+                    seen.push(pair.loser);
+                    stack.push(pair.loser);
+                }
+            }
+        }
+        return false;
+    }
+
+    // Locks `pairs` in order, skipping any that would create a cycle.
+    fn lock_pairs(pairs: Vec<Pair>) -> (Vec<Pair>, Vec<Pair>) {
+        let mut locked: Vec<Pair> = Vec::new();
+        let mut skipped: Vec<Pair> = Vec::new();
+        for pair in pairs {
+            if reaches(&locked, pair.loser, pair.winner) {
+                skipped.push(pair);
+            } else {
+                locked.push(pair);
+            }
+        }
+        return (locked, skipped);
+    }
+
+    // Groups candidates by layers of the locked graph: first everyone no
+    // locked pair beats, then everyone only beaten by the first group, ...
+    fn locked_ranking(num_choices: u32, locked: &[Pair]) -> Vec<Vec<cv::BallotChoice>> {
+        let mut remaining: Vec<cv::BallotChoice> = (0..num_choices).collect();
+        let mut ranking: Vec<Vec<cv::BallotChoice>> = Vec::new();
+        while !remaining.is_empty() {
+            let (sources, rest): (Vec<cv::BallotChoice>, Vec<cv::BallotChoice>) =
+                remaining.iter().partition(|choice| {
+                    !locked
+                        .iter()
+                        .any(|pair| pair.loser == **choice && remaining.contains(&pair.winner))
+                });
+            ranking.push(sources);
+            remaining = rest;
+        }
+        return ranking;
+    }
+
+    #[test]
+    fn test_vote() {
+        // https://en.wikipedia.org/wiki/Ranked_pairs#Example
+        let ballots = cv::tennessee_ballots();
+
+        let result = vote(4, ballots, &cv::TieBreaker::None).unwrap();
+        assert_eq!(result.winner, Some(1));
+        assert!(result.skipped.is_empty());
+        assert_eq!(
+            (result.locked[0].winner, result.locked[0].loser),
+            (2, 3) // Chattanooga over Knoxville, 83-17
+        );
+
+        // A beats B beats C beats A; the weakest win, C over A, is skipped.
+        let mut ballots: Vec<cv::Ballot> = Vec::new();
+        ballots.extend(vec![vec![vec![0], vec![1], vec![2]]; 3]);
+        ballots.extend(vec![vec![vec![1], vec![2], vec![0]]; 2]);
+        ballots.extend(vec![vec![vec![2], vec![0], vec![1]]; 2]);
+
+        let result = vote(3, ballots, &cv::TieBreaker::None).unwrap();
+        assert_eq!(result.winner, Some(0));
+        assert_eq!(result.locked.len(), 2);
+        assert_eq!(
+            result.skipped,
+            vec![Pair {
+                winner: 2,
+                loser: 0,
+                votes_for: 4,
+                votes_against: 3,
+            }]
+        );
+
+        assert!(vote(3, vec![], &cv::TieBreaker::None).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<cv::Ballot>,
+        tie_breaker: &cv::TieBreaker,
+    ) -> Result<VoteBreakdown, cv::VoteError> {
+        let final_outranking = cv::outranking(num_choices, &ballots)?;
+
+        let mut pairs = sorted_pairs(&final_outranking, None);
+        let pairs_tied = pairs
+            .windows(2)
+            .any(|w| w[0].votes_for == w[1].votes_for && w[0].votes_against == w[1].votes_against);
+
+        // Only draw a tie-breaking order when something is actually tied.
+        let mut tie_break_order = None;
+        if pairs_tied {
+            tie_break_order =
+                cv::tie_break_order(&ballots, &vec![1; ballots.len()], num_choices, tie_breaker);
+            pairs = sorted_pairs(&final_outranking, tie_break_order.as_deref());
+        }
+
+        let (locked, skipped) = lock_pairs(pairs);
+        let tied_winners = locked_ranking(num_choices, &locked)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        if tied_winners.len() > 1 && tie_break_order.is_none() {
+            tie_break_order =
+                cv::tie_break_order(&ballots, &vec![1; ballots.len()], num_choices, tie_breaker);
+        }
+        let winner = match (tied_winners.as_slice(), &tie_break_order) {
+            ([winner], _) => Some(*winner),
+            (_, Some(order)) => order
+                .iter()
+                .find(|choice| tied_winners.contains(choice))
+                .copied(),
+            (_, None) => None,
+        };
+
+        return Ok(VoteBreakdown {
+            winner,
+            tied_winners,
+            locked,
+            skipped,
+            tie_breaker: *tie_breaker,
+            tie_break_order,
+            final_outranking,
+        });
+    }
+
+    /// Ranked Pairs (Tideman) on ranked ballots.
+    pub struct RankedPairs {
+        pub tie_breaker: cv::TieBreaker,
+    }
+
+    impl vm::VotingMethod for RankedPairs {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = cv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "Ranked Pairs"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, cv::VoteError> {
+            let breakdown = vote(num_choices, ballots, &self.tie_breaker)?;
+
+            let ranking = locked_ranking(num_choices, &breakdown.locked);
+            let ranking = match &breakdown.tie_break_order {
+                Some(order) => cv::break_ranking_ties(ranking, order),
+                None => ranking,
+            };
+            let winners = match breakdown.winner {
+                Some(winner) => vec![winner],
+                None => breakdown.tied_winners.clone(),
+            };
+
+            return Ok(vm::Outcome {
+                winners,
+                ranking,
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let pair_str = |pair: &Pair| {
+                format!(
+                    "{} over {} ({}–{})",
+                    vm::option_label(pair.winner, option_id_to_option),
+                    vm::option_label(pair.loser, option_id_to_option),
+                    pair.votes_for,
+                    pair.votes_against,
+                )
+            };
+
+            let mut message = match result.winner {
+                Some(winner) => format!(
+                    "Winner via Ranked Pairs: {winner} - **{}**.",
+                    vm::option_name(winner, option_id_to_option),
+                ),
+                None => "No single winner via Ranked Pairs.".to_string(),
+            };
+            message += &cv::tie_message(
+                &result.tied_winners,
+                &result.tie_breaker,
+                &result.tie_break_order,
+                option_id_to_option,
+            );
+            message += &format!(
+                "\nLocked in: {}",
+                result
+                    .locked
+                    .iter()
+                    .map(pair_str)
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            );
+            if !result.skipped.is_empty() {
+                message += &format!(
+                    "\nSkipped (would create a cycle): {}",
+                    result
+                        .skipped
+                        .iter()
+                        .map(pair_str)
+                        .collect::<Vec<String>>()
+                        .join("; ")
+                );
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        // Everyone ties 0 and 1 above 2.
+        let ballots = vec![cv::Ballot::from([vec![0, 1], vec![2]]); 2];
+
+        let unbroken = RankedPairs {
+            tie_breaker: cv::TieBreaker::None,
+        }
+        .tally(3, ballots.clone())
+        .unwrap();
+        assert_eq!(unbroken.winners, vec![0, 1]);
+        assert_eq!(unbroken.ranking, vec![vec![0, 1], vec![2]]);
+
+        let method = RankedPairs {
+            tie_breaker: cv::TieBreaker::RandomOrder { seed: 5 },
+        };
+        let broken = method.tally(3, ballots.clone()).unwrap();
+        assert_eq!(broken.winners.len(), 1);
+        assert_eq!(broken.ranking.len(), 3);
+        assert_eq!(broken.ranking[2], vec![2]);
+        assert_eq!(method.tally(3, ballots).unwrap(), broken);
+
+        let options = BTreeMap::from([
+            (0, "Dune".to_string()),
+            (1, "Emma".to_string()),
+            (2, "Moby".to_string()),
+        ]);
+        assert!(
+            method
+                .describe(&broken, &options)
+                .contains("0 (Dune) over 2 (Moby) (2–0)")
+        );
+    }
+}
@@ -0,0 +1,262 @@
+pub mod sv {
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+    use std::error::Error;
+    use std::fmt;
+
+    pub type BallotChoice = u32;
+    pub type Score = u32;
+    /// Scores by option. Options left off a ballot score 0.
+    pub type Ballot = BTreeMap<BallotChoice, Score>;
+
+    pub const MAX_SCORE: Score = 5;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// Every option sharing the highest total score.
+        pub winners: Vec<BallotChoice>,
+        pub totals: BTreeMap<BallotChoice, u64>,
+        pub num_ballots: u64,
+    }
+
+    impl std::fmt::Display for VoteBreakdown {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str(&format!(
+                "Winners - {:?}. Totals: {:?} from {} ballots",
+                self.winners, self.totals, self.num_ballots
+            ))
+        }
+    }
+
+    /// Why score ballots could not be counted. Ballots are numbered from 0
+    /// in the order they were passed in.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum VoteError {
+        NoBallots,
+        UnknownOption {
+            ballot: usize,
+            choice: BallotChoice,
+        },
+        /// A score above `MAX_SCORE`.
+        InvalidScore {
+            ballot: usize,
+            choice: BallotChoice,
+            score: Score,
+        },
+    }
+
+    impl fmt::Display for VoteError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                VoteError::NoBallots => write!(f, "Must have at least one ballot to vote"),
+                VoteError::UnknownOption { ballot, choice } => {
+                    write!(f, "Ballot {ballot} scores unknown option {choice}")
+                }
+                VoteError::InvalidScore {
+                    ballot,
+                    choice,
+                    score,
+                } => write!(
+                    f,
+                    "Ballot {ballot} scores option {choice} {score}, above the maximum of {MAX_SCORE}"
+                ),
+            }
+        }
+    }
+
+    impl Error for VoteError {}
+
+    /// Checks that there are ballots and that every score is for a valid
+    /// option and at most `MAX_SCORE`.
+    pub fn validate(num_choices: u32, ballots: &[Ballot]) -> Result<(), VoteError> {
+        if ballots.is_empty() {
+            return Err(VoteError::NoBallots);
+        }
+
+        for (i, ballot) in ballots.iter().enumerate() {
+            for (choice, score) in ballot.iter() {
+                if *choice >= num_choices {
+                    return Err(VoteError::UnknownOption {
+                        ballot: i,
+                        choice: *choice,
+                    });
+                }
+                if *score > MAX_SCORE {
+                    return Err(VoteError::InvalidScore {
+                        ballot: i,
+                        choice: *choice,
+                        score: *score,
+                    });
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    /// Total score of every option.
+    pub fn totals(num_choices: u32, ballots: &[Ballot]) -> BTreeMap<BallotChoice, u64> {
+        let mut totals: BTreeMap<BallotChoice, u64> =
+            (0..num_choices).map(|choice| (choice, 0)).collect();
+        for (choice, score) in ballots.iter().flatten() {
+            *totals.entry(*choice).or_default() += u64::from(*score);
+        }
+        return totals;
+    }
+
+    #[test]
+    fn test_vote() {
+        struct Case {
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+            expected_winners: Vec<BallotChoice>,
+            expected_totals: BTreeMap<BallotChoice, u64>,
+        }
+
+        let cases = [
+            Case {
+                num_choices: 3,
+                ballots: vec![
+                    Ballot::from([(0, 5), (1, 3)]),
+                    Ballot::from([(0, 1), (1, 4), (2, 2)]),
+                ],
+                expected_winners: vec![1],
+                expected_totals: BTreeMap::from([(0, 6), (1, 7), (2, 2)]),
+            },
+            Case {
+                num_choices: 2,
+                ballots: vec![Ballot::from([(0, 5)]), Ballot::from([(1, 5)])],
+                expected_winners: vec![0, 1],
+                expected_totals: BTreeMap::from([(0, 5), (1, 5)]),
+            },
+        ];
+
+        for case in cases.iter() {
+            let result = vote(case.num_choices, case.ballots.clone()).unwrap();
+            assert_eq!(result.winners, case.expected_winners);
+            assert_eq!(result.totals, case.expected_totals);
+        }
+
+        assert_eq!(vote(2, vec![]), Err(VoteError::NoBallots));
+        assert_eq!(
+            vote(2, vec![Ballot::from([(2, 1)])]),
+            Err(VoteError::UnknownOption {
+                ballot: 0,
+                choice: 2
+            })
+        );
+        assert_eq!(
+            vote(2, vec![Ballot::from([(0, 6)])]),
+            Err(VoteError::InvalidScore {
+                ballot: 0,
+                choice: 0,
+                score: 6
+            })
+        );
+    }
+
+    pub fn vote(num_choices: u32, ballots: Vec<Ballot>) -> Result<VoteBreakdown, VoteError> {
+        validate(num_choices, &ballots)?;
+
+        let totals = totals(num_choices, &ballots);
+        let best = totals.values().max().copied().unwrap_or(0);
+        let winners = totals
+            .iter()
+            .filter(|(_, total)| **total == best)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        return Ok(VoteBreakdown {
+            winners,
+            totals,
+            num_ballots: vm::count(ballots.len()),
+        });
+    }
+
+    /// Formats a total score with its average per ballot, e.g. "7 (avg 3.50)".
+    pub fn total_str(total: u64, num_ballots: u64) -> String {
+        if num_ballots == 0 {
+            return total.to_string();
+        }
+        // Hundredths, rounded half up.
+        let hundredths = (total * 200 + num_ballots) / (2 * num_ballots);
+        return format!(
+            "{total} (avg {}.{:0>2})",
+            hundredths / 100,
+            hundredths % 100
+        );
+    }
+
+    /// Score voting: each voter scores every option from 0 to `MAX_SCORE`
+    /// and the highest total wins.
+    pub struct ScoreVoting;
+
+    impl vm::VotingMethod for ScoreVoting {
+        type Ballot = Ballot;
+        type Details = VoteBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            "Score"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking: vm::ranking_by_score(breakdown.totals.clone()),
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("Score winner: {}.", winners[0]),
+                _ => format!("Tied score winners: {}.", winners.join(", ")),
+            };
+
+            let totals: Vec<String> = result
+                .totals
+                .iter()
+                .map(|(choice, total)| {
+                    format!(
+                        "{} {}",
+                        vm::option_label(*choice, option_id_to_option),
+                        total_str(*total, result.num_ballots)
+                    )
+                })
+                .collect();
+            message += &format!("\nTotal scores: {}", totals.join(", "));
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![Ballot::from([(0, 5), (1, 3)]), Ballot::from([(0, 2)])];
+        let outcome = ScoreVoting.tally(2, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            ScoreVoting.describe(&outcome, &options),
+            "Score winner: **Dune**.\nTotal scores: 0 (Dune) 7 (avg 3.50), 1 (Emma) 3 (avg 1.50)"
+        );
+    }
+}
@@ -0,0 +1,392 @@
+pub mod sensitivity {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    /// How many resampled elections `bootstrap` counts.
+    pub const RESAMPLES: u32 = 1000;
+
+    /// How few ballots would have overturned a Condorcet/Schulze winner.
+    /// Each count is for ballots ranking one challenger first and the
+    /// winner last, which is the most direct attack but not proven minimal
+    /// under Schulze, so both are upper bounds.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Margin {
+        pub winner: cv::BallotChoice,
+        /// The fewest new ballots of weight 1 after which `winner` is no
+        /// longer the only winner.
+        pub added: u64,
+        pub added_challenger: cv::BallotChoice,
+        /// The fewest existing ballots that would have to be changed, and the
+        /// challenger they would rank first.
+        pub changed: Option<(usize, cv::BallotChoice)>,
+    }
+
+    /// How often each option won when the ballots were resampled with
+    /// replacement.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Bootstrap {
+        pub resamples: u32,
+        pub seed: u64,
+        /// Resamples each option won outright.
+        pub wins: BTreeMap<cv::BallotChoice, u32>,
+        /// Resamples with no single winner before tie-breaking.
+        pub ties: u32,
+    }
+
+    // A ballot ranking `challenger` first, `winner` last and every other
+    // option tied in between.
+    fn challenge(
+        num_choices: u32,
+        challenger: cv::BallotChoice,
+        winner: cv::BallotChoice,
+    ) -> cv::Ballot {
+        let others: Vec<cv::BallotChoice> = (0..num_choices)
+            .filter(|choice| *choice != challenger && *choice != winner)
+            .collect();
+        return [vec![challenger], others, vec![winner]]
+            .into_iter()
+            .filter(|tier| !tier.is_empty())
+            .collect();
+    }
+
+    // Adds `ballot`, worth `units` votes, to `matrix` in place.
+    fn add_ballot(
+        matrix: &mut nalgebra::DMatrix<u32>,
+        ballot: &nalgebra::DMatrix<u32>,
+        units: u32,
+    ) {
+        for (votes, ranked) in matrix.iter_mut().zip(ballot.iter()) {
+            *votes = votes.saturating_add(ranked.saturating_mul(units));
+        }
+    }
+
+    // Whether `ballot` ranks `a` above `b`. Unranked options tie below every
+    // ranked one.
+    fn ranks_above(ballot: &cv::Ballot, a: cv::BallotChoice, b: cv::BallotChoice) -> bool {
+        let position = |choice: cv::BallotChoice| {
+            ballot
+                .iter()
+                .position(|tier| tier.contains(&choice))
+                .unwrap_or(ballot.len())
+        };
+        return position(a) < position(b);
+    }
+
+    // How many votes, in 1/`vote_unit`, ballot `i` of `result` carries.
+    fn units(result: &cv::VoteBreakdown, i: usize) -> u32 {
+        let weight = result.weights[i];
+        return weight.numer() * (result.vote_unit / weight.denom());
+    }
+
+    /// The margin of `result`'s winner, or `None` if no option won before
+    /// tie-breaking.
+    pub fn margin(result: &cv::VoteBreakdown) -> Option<Margin> {
+        let overall_matrix = &result.final_outranking;
+        let num_choices = vm::option_id(overall_matrix.nrows());
+        let winner = match cv::winners(overall_matrix, result.strength).as_slice() {
+            [winner] => *winner,
+            _ => return None,
+        };
+        let overturned =
+            |matrix: &nalgebra::DMatrix<u32>| cv::winners(matrix, result.strength) != [winner];
+
+        let mut added: Option<(u64, cv::BallotChoice)> = None;
+        let mut changed: Option<(usize, cv::BallotChoice)> = None;
+        for challenger in (0..num_choices).filter(|choice| *choice != winner) {
+            let attack = cv::ballot_to_outranking_matrix(
+                &challenge(num_choices, challenger, winner),
+                num_choices,
+            );
+
+            // Each new ballot only moves the result towards the challenger,
+            // so double the count until the winner falls and then binary
+            // search for the fewest. Enough ballots always make the
+            // challenger a Condorcet winner, so the doubling ends.
+            let mut matrix = overall_matrix.clone();
+            let mut overturned_by = |count: u64| {
+                matrix.copy_from(overall_matrix);
+                let units = count.saturating_mul(u64::from(result.vote_unit));
+                add_ballot(
+                    &mut matrix,
+                    &attack,
+                    u32::try_from(units).unwrap_or(u32::MAX),
+                );
+                return overturned(&matrix);
+            };
+            let (mut holds, mut count) = (0, 1);
+            while !overturned_by(count) {
+                (holds, count) = (count, count.saturating_mul(2));
+            }
+            while count - holds > 1 {
+                let middle = holds + (count - holds) / 2;
+                match overturned_by(middle) {
+                    true => count = middle,
+                    false => holds = middle,
+                }
+            }
+            if added.is_none_or(|(fewest, _)| count < fewest) {
+                added = Some((count, challenger));
+            }
+
+            // Change the ballots that help the winner most first: those
+            // ranking it above the challenger, heaviest first.
+            let mut order: Vec<usize> = (0..result.ballots.len()).collect();
+            order.sort_by_key(|i| {
+                (
+                    std::cmp::Reverse(ranks_above(&result.ballots[*i], winner, challenger)),
+                    std::cmp::Reverse(units(result, *i)),
+                )
+            });
+            matrix.copy_from(overall_matrix);
+            for (count, i) in order.into_iter().enumerate() {
+                if changed.is_some_and(|(fewest, _)| count + 1 >= fewest) {
+                    break;
+                }
+                // One ballot's matrix at a time, never one for every ballot.
+                let own = cv::ballot_to_outranking_matrix(&result.ballots[i], num_choices);
+                for (votes, ranked) in matrix.iter_mut().zip(own.iter()) {
+                    *votes -= ranked * units(result, i);
+                }
+                add_ballot(&mut matrix, &attack, units(result, i));
+                if overturned(&matrix) {
+                    changed = Some((count + 1, challenger));
+                    break;
+                }
+            }
+        }
+
+        let (added, added_challenger) = added?;
+        return Some(Margin {
+            winner,
+            added,
+            added_challenger,
+            changed,
+        });
+    }
+
+    #[test]
+    fn test_margin() {
+        let result = |ballots: Vec<cv::WeightedBallot>| {
+            cv::weighted_vote(
+                3,
+                ballots,
+                &cv::TieBreaker::None,
+                cv::Strength::WinningVotes,
+            )
+            .unwrap()
+        };
+        let ballots = |count: usize, ballot: cv::Ballot| {
+            vec![
+                cv::WeightedBallot {
+                    weight: cv::Weight::from(1),
+                    ballot,
+                };
+                count
+            ]
+        };
+
+        // 3–2: one more ballot for 1 ties it, as does changing one.
+        let mut close = ballots(3, vec![vec![0], vec![1], vec![2]]);
+        close.extend(ballots(2, vec![vec![1], vec![0], vec![2]]));
+        assert_eq!(
+            margin(&result(close)),
+            Some(Margin {
+                winner: 0,
+                added: 1,
+                added_challenger: 1,
+                changed: Some((1, 1)),
+            })
+        );
+
+        // 5–1: four new ballots or two changed ones.
+        let mut clear = ballots(5, vec![vec![0], vec![1], vec![2]]);
+        clear.extend(ballots(1, vec![vec![1], vec![0], vec![2]]));
+        assert_eq!(
+            margin(&result(clear)),
+            Some(Margin {
+                winner: 0,
+                added: 4,
+                added_challenger: 1,
+                changed: Some((2, 1)),
+            })
+        );
+
+        // 100–1: 99 new ballots tie it, found without adding them one by
+        // one.
+        let mut landslide = ballots(100, vec![vec![0], vec![1], vec![2]]);
+        landslide.extend(ballots(1, vec![vec![1], vec![0], vec![2]]));
+        let landslide = margin(&result(landslide)).unwrap();
+        assert_eq!((landslide.added, landslide.added_challenger), (99, 1));
+
+        // Changing the one ballot for 0, worth three votes, is enough.
+        let mut weighted = ballots(2, vec![vec![1], vec![0], vec![2]]);
+        weighted.push(cv::WeightedBallot {
+            weight: cv::Weight::from(3),
+            ballot: vec![vec![0], vec![1], vec![2]],
+        });
+        let weighted = margin(&result(weighted)).unwrap();
+        assert_eq!(weighted.added, 1);
+        assert_eq!(weighted.changed, Some((1, 1)));
+
+        // No winner before tie-breaking.
+        let mut tied = ballots(1, vec![vec![0], vec![1]]);
+        tied.extend(ballots(1, vec![vec![1], vec![0]]));
+        assert_eq!(margin(&result(tied)), None);
+    }
+
+    /// Counts `resamples` elections, each drawing as many ballots as
+    /// `result` has at random with replacement, seeded with `seed`.
+    pub fn bootstrap(result: &cv::VoteBreakdown, resamples: u32, seed: u64) -> Bootstrap {
+        let num_choices = vm::option_id(result.final_outranking.nrows());
+        let num_ballots = result.ballots.len();
+
+        let mut rng = vm::SeededRng::new(seed);
+        let mut wins: BTreeMap<cv::BallotChoice, u32> =
+            (0..num_choices).map(|choice| (choice, 0)).collect();
+        let mut ties = 0;
+        // How often each ballot was drawn, reused for every resample.
+        let mut draws: Vec<u64> = vec![0; num_ballots];
+        for _ in 0..resamples {
+            draws.fill(0);
+            for _ in 0..num_ballots {
+                draws[rng.below(num_ballots)] += 1;
+            }
+            let counted = cv::counted_outranking(num_choices, &result.ballots, |i| {
+                draws[i] * u64::from(units(result, i))
+            });
+            // A resample too heavy to count has no winner to report.
+            match counted.map(|matrix| cv::winners(&matrix, result.strength)) {
+                Ok(winners) if winners.len() == 1 => *wins.entry(winners[0]).or_default() += 1,
+                _ => ties += 1,
+            }
+        }
+
+        return Bootstrap {
+            resamples,
+            seed,
+            wins,
+            ties,
+        };
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let vote = |ballots: Vec<cv::Ballot>| {
+            cv::vote(
+                2,
+                ballots,
+                &cv::TieBreaker::None,
+                cv::Strength::WinningVotes,
+            )
+            .unwrap()
+        };
+
+        let unanimous = bootstrap(&vote(vec![vec![vec![1], vec![0]]; 3]), 50, 7);
+        assert_eq!(unanimous.wins, BTreeMap::from([(0, 0), (1, 50)]));
+        assert_eq!(unanimous.ties, 0);
+
+        let mut ballots = vec![vec![vec![0], vec![1]]; 3];
+        ballots.extend(vec![vec![vec![1], vec![0]]; 2]);
+        let close = bootstrap(&vote(ballots.clone()), 200, 7);
+        assert_eq!(close.wins.values().sum::<u32>() + close.ties, 200);
+        assert!(close.wins[&0] > close.wins[&1]);
+        assert!(close.wins[&1] > 0);
+        assert_eq!(bootstrap(&vote(ballots), 200, 7), close);
+    }
+
+    // `part` of `whole` as a whole percentage.
+    fn percent(part: u32, whole: u32) -> u32 {
+        if whole == 0 {
+            return 0;
+        }
+        return (part * 100 + whole / 2) / whole;
+    }
+
+    /// The margin and bootstrap estimate for a results post, using option
+    /// names.
+    pub fn describe(
+        margin: &Option<Margin>,
+        bootstrap: &Bootstrap,
+        num_ballots: usize,
+        option_id_to_option: &BTreeMap<vm::OptionId, String>,
+    ) -> String {
+        let name = |choice: cv::BallotChoice| vm::option_name(choice, option_id_to_option);
+        let mut message = match margin {
+            Some(margin) => {
+                let mut message = format!(
+                    "Margin of victory for **{}**: at most {} more {} ranking **{}** first and **{}** last would change the winner",
+                    name(margin.winner),
+                    margin.added,
+                    if margin.added == 1 {
+                        "ballot"
+                    } else {
+                        "ballots"
+                    },
+                    name(margin.added_challenger),
+                    name(margin.winner),
+                );
+                if let Some((changed, challenger)) = margin.changed {
+                    message += &format!(
+                        "; so would changing at most {changed} {} to rank **{}** first and **{}** last",
+                        if changed == 1 { "ballot" } else { "ballots" },
+                        name(challenger),
+                        name(margin.winner),
+                    );
+                }
+                message + "."
+            }
+            None => "There was no single winner before tie-breaking, so no margin of victory."
+                .to_string(),
+        };
+
+        let mut wins: Vec<(cv::BallotChoice, u32)> = bootstrap
+            .wins
+            .iter()
+            .filter(|(_, wins)| **wins > 0)
+            .map(|(choice, wins)| (*choice, *wins))
+            .collect();
+        wins.sort_by_key(|(_, wins)| std::cmp::Reverse(*wins));
+        let mut shares: Vec<String> = wins
+            .iter()
+            .map(|(choice, wins)| {
+                format!("{} {}%", name(*choice), percent(*wins, bootstrap.resamples))
+            })
+            .collect();
+        if bootstrap.ties > 0 {
+            shares.push(format!(
+                "tied {}%",
+                percent(bootstrap.ties, bootstrap.resamples)
+            ));
+        }
+        message += &format!(
+            "\nWins over {} resamples of the {num_ballots} ballots (seed {}): {}.",
+            bootstrap.resamples,
+            bootstrap.seed,
+            shares.join(", ")
+        );
+        return message;
+    }
+
+    #[test]
+    fn test_describe() {
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        let margin = Some(Margin {
+            winner: 0,
+            added: 2,
+            added_challenger: 1,
+            changed: Some((1, 1)),
+        });
+        let bootstrap = Bootstrap {
+            resamples: 1000,
+            seed: 9,
+            wins: BTreeMap::from([(0, 720), (1, 250), (2, 0)]),
+            ties: 30,
+        };
+        assert_eq!(
+            describe(&margin, &bootstrap, 5, &options),
+            "Margin of victory for **Dune**: at most 2 more ballots ranking **Emma** first and **Dune** last would change the winner; so would changing at most 1 ballot to rank **Emma** first and **Dune** last.
+Wins over 1000 resamples of the 5 ballots (seed 9): Dune 72%, Emma 25%, tied 3%."
+        );
+    }
+}
@@ -0,0 +1,539 @@
+pub mod stv {
+    use crate::condorcet_voting::cv;
+    use crate::voting_method::vm;
+    use num_rational::BigRational;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::error::Error;
+    use std::fmt;
+
+    pub type BallotChoice = u32;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        pub seats: u32,
+        /// Droop quota: floor(ballots / (seats + 1)) + 1.
+        pub quota: u64,
+        /// Elected candidates in the order they were elected.
+        pub elected: Vec<BallotChoice>,
+        pub rounds: Vec<RoundBreakdown>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RoundBreakdown {
+        /// Votes held by each continuing candidate at the start of the round.
+        pub votes_by_candidate: BTreeMap<BallotChoice, BigRational>,
+        pub elected: Vec<BallotChoice>,
+        pub eliminated: Option<BallotChoice>,
+        /// Votes received by each candidate from this round's transfers.
+        pub transfers: BTreeMap<BallotChoice, BigRational>,
+        /// Votes with no continuing preference left after this round's transfers.
+        pub exhausted: BigRational,
+    }
+
+    impl std::fmt::Display for VoteBreakdown {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str(&format!(
+                "Seats {}, quota {}, elected {:?}\n",
+                self.seats, self.quota, self.elected
+            ))?;
+            for (i, round) in self.rounds.iter().enumerate() {
+                fmt.write_str(&format!(
+                    "Round {}: votes {:?}, elected {:?}, eliminated {:?}, transfers {:?}, exhausted {}\n",
+                    i + 1,
+                    round.votes_by_candidate,
+                    round.elected,
+                    round.eliminated,
+                    round.transfers,
+                    round.exhausted,
+                ))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum VoteError {
+        /// Seats must be between 1 and the number of options.
+        InvalidSeats { seats: u32, num_choices: u32 },
+        /// The ballots themselves are invalid.
+        Ballots(cv::VoteError),
+    }
+
+    impl fmt::Display for VoteError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                VoteError::InvalidSeats { seats, num_choices } => {
+                    write!(f, "Cannot fill {seats} seats from {num_choices} options")
+                }
+                VoteError::Ballots(err) => write!(f, "{err}"),
+            }
+        }
+    }
+
+    impl Error for VoteError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                VoteError::InvalidSeats { .. } => None,
+                VoteError::Ballots(err) => Some(err),
+            }
+        }
+    }
+
+    impl From<cv::VoteError> for VoteError {
+        fn from(err: cv::VoteError) -> VoteError {
+            return VoteError::Ballots(err);
+        }
+    }
+
+    fn ratio(n: u64) -> BigRational {
+        return BigRational::from_integer(n.into());
+    }
+
+    // A share of one ballot sitting on a candidate's pile.
+    #[derive(Debug, Clone)]
+    struct Parcel {
+        ballot: usize,
+        weight: BigRational,
+    }
+
+    #[test]
+    fn test_next_preferences() {
+        let continuing = BTreeSet::from([1, 2, 3]);
+        assert_eq!(
+            next_preferences(&vec![vec![0], vec![2], vec![1]], &continuing),
+            vec![2]
+        );
+        assert_eq!(
+            next_preferences(&vec![vec![0], vec![3, 1, 4]], &continuing),
+            vec![3, 1]
+        );
+        assert_eq!(
+            next_preferences(&vec![vec![0]], &continuing),
+            Vec::<BallotChoice>::new()
+        );
+    }
+
+    // The continuing candidates in the highest tier of `ballot` that has any.
+    // The ballot is shared equally between them.
+    fn next_preferences(
+        ballot: &cv::Ballot,
+        continuing: &BTreeSet<BallotChoice>,
+    ) -> Vec<BallotChoice> {
+        return ballot
+            .iter()
+            .map(|tier| {
+                tier.iter()
+                    .filter(|choice| continuing.contains(choice))
+                    .copied()
+                    .collect::<Vec<BallotChoice>>()
+            })
+            .find(|tier| !tier.is_empty())
+            .unwrap_or_default();
+    }
+
+    // Moves `parcels` to their next continuing preferences, scaled by
+    // `transfer_value`. Returns the votes each candidate received and the
+    // votes that exhausted.
+    fn transfer(
+        parcels: Vec<Parcel>,
+        transfer_value: &BigRational,
+        ballots: &[cv::Ballot],
+        continuing: &BTreeSet<BallotChoice>,
+        piles: &mut BTreeMap<BallotChoice, Vec<Parcel>>,
+    ) -> (BTreeMap<BallotChoice, BigRational>, BigRational) {
+        let mut received: BTreeMap<BallotChoice, BigRational> = BTreeMap::new();
+        let mut exhausted = ratio(0);
+        for parcel in parcels {
+            let weight = parcel.weight * transfer_value;
+            let next = next_preferences(&ballots[parcel.ballot], continuing);
+            if next.is_empty() {
+                exhausted += weight;
+                continue;
+            }
+
+            let share = weight / ratio(vm::count(next.len()));
+            for choice in next {
+                *received.entry(choice).or_insert_with(|| ratio(0)) += &share;
+                piles.entry(choice).or_default().push(Parcel {
+                    ballot: parcel.ballot,
+                    weight: share.clone(),
+                });
+            }
+        }
+        return (received, exhausted);
+    }
+
+    fn pile_votes(pile: Option<&Vec<Parcel>>) -> BigRational {
+        return pile
+            .into_iter()
+            .flatten()
+            .fold(ratio(0), |acc, parcel| acc + &parcel.weight);
+    }
+
+    // The continuing candidate with the fewest votes. Ties go to whoever had
+    // fewer votes in the latest earlier round that tells them apart, and
+    // finally to the highest option ID.
+    fn pick_eliminate(
+        votes_by_candidate: &BTreeMap<BallotChoice, BigRational>,
+        rounds: &[RoundBreakdown],
+    ) -> BallotChoice {
+        let fewest = votes_by_candidate.values().min().unwrap();
+        let mut tied: Vec<BallotChoice> = votes_by_candidate
+            .iter()
+            .filter(|(_, votes)| *votes == fewest)
+            .map(|(choice, _)| *choice)
+            .collect();
+
+        for round in rounds.iter().rev() {
+            if tied.len() == 1 {
+                break;
+            }
+            let votes = |choice: &BallotChoice| {
+                round
+                    .votes_by_candidate
+                    .get(choice)
+                    .cloned()
+                    .unwrap_or_else(|| ratio(0))
+            };
+            let fewest = tied.iter().map(votes).min().unwrap();
+            tied.retain(|choice| votes(choice) == fewest);
+        }
+        return *tied.iter().max().unwrap();
+    }
+
+    #[test]
+    fn test_vote() {
+        // https://en.wikipedia.org/wiki/Single_transferable_vote#Example
+        // Orange 0, Pear 1, Chocolate 2, Strawberry 3, Bonbon 4
+        let mut ballots: Vec<cv::Ballot> = Vec::new();
+        ballots.extend(vec![vec![vec![0]]; 4]);
+        ballots.extend(vec![vec![vec![1], vec![0]]; 2]);
+        ballots.extend(vec![vec![vec![2], vec![3]]; 8]);
+        ballots.extend(vec![vec![vec![2], vec![4]]; 4]);
+        ballots.extend(vec![vec![vec![3]]; 1]);
+        ballots.extend(vec![vec![vec![4]]; 1]);
+
+        let result = vote(5, 3, ballots).unwrap();
+        assert_eq!(result.quota, 6);
+        assert_eq!(result.elected, vec![2, 0, 3]);
+
+        // Chocolate's surplus of 6 moves on at half value.
+        assert_eq!(result.rounds[0].elected, vec![2]);
+        assert_eq!(
+            result.rounds[0].transfers,
+            BTreeMap::from([(3, ratio(4)), (4, ratio(2))])
+        );
+        assert_eq!(result.rounds[1].eliminated, Some(1));
+        assert_eq!(result.rounds[2].elected, vec![0]);
+        assert_eq!(result.rounds[3].eliminated, Some(4));
+        assert_eq!(result.rounds[3].exhausted, ratio(3));
+        assert_eq!(result.rounds[4].elected, vec![3]);
+
+        // Equally ranked options share a ballot. 1 gets 2 + 3/2 votes and 2
+        // gets 3/2, so 1 is elected with a surplus of 1/2.
+        let ballots = vec![
+            vec![vec![0]],
+            vec![vec![0]],
+            vec![vec![1]],
+            vec![vec![1]],
+            vec![vec![1, 2], vec![0]],
+            vec![vec![1, 2], vec![0]],
+            vec![vec![1, 2], vec![0]],
+        ];
+        let result = vote(3, 1, ballots).unwrap();
+        assert_eq!(result.quota, 4);
+        assert_eq!(
+            result.rounds[0].votes_by_candidate,
+            BTreeMap::from([
+                (0, ratio(2)),
+                (1, ratio(7) / ratio(2)),
+                (2, ratio(3) / ratio(2))
+            ])
+        );
+        assert_eq!(result.rounds[0].eliminated, Some(2));
+        assert_eq!(result.elected, vec![1]);
+
+        assert!(vote(2, 3, vec![vec![vec![0]]]).is_err());
+        assert_eq!(
+            vote(2, 0, vec![vec![vec![0]]]),
+            Err(VoteError::InvalidSeats {
+                seats: 0,
+                num_choices: 2
+            })
+        );
+        assert_eq!(
+            vote(2, 1, vec![]),
+            Err(VoteError::Ballots(cv::VoteError::NoBallots))
+        );
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        seats: u32,
+        ballots: Vec<cv::Ballot>,
+    ) -> Result<VoteBreakdown, VoteError> {
+        if seats == 0 || seats > num_choices {
+            return Err(VoteError::InvalidSeats { seats, num_choices });
+        }
+        // Reuse the ranked ballot validation.
+        cv::outranking(num_choices, &ballots)?;
+
+        let quota = vm::count(ballots.len()) / (u64::from(seats) + 1) + 1;
+        let mut continuing: BTreeSet<BallotChoice> = (0..num_choices).collect();
+        let mut piles: BTreeMap<BallotChoice, Vec<Parcel>> = BTreeMap::new();
+        let first_preferences: Vec<Parcel> = (0..ballots.len())
+            .map(|ballot| Parcel {
+                ballot,
+                weight: ratio(1),
+            })
+            .collect();
+        let (_, mut exhausted) = transfer(
+            first_preferences,
+            &ratio(1),
+            &ballots,
+            &continuing,
+            &mut piles,
+        );
+
+        let mut elected: Vec<BallotChoice> = Vec::new();
+        let mut rounds: Vec<RoundBreakdown> = Vec::new();
+        while elected.len() < vm::index(seats) && !continuing.is_empty() {
+            let votes_by_candidate: BTreeMap<BallotChoice, BigRational> = continuing
+                .iter()
+                .map(|choice| (*choice, pile_votes(piles.get(choice))))
+                .collect();
+            let mut round = RoundBreakdown {
+                votes_by_candidate: votes_by_candidate.clone(),
+                elected: Vec::new(),
+                eliminated: None,
+                transfers: BTreeMap::new(),
+                exhausted,
+            };
+
+            let mut by_votes: Vec<(BallotChoice, BigRational)> =
+                votes_by_candidate.clone().into_iter().collect();
+            by_votes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+            let seats_left = vm::index(seats) - elected.len();
+            if continuing.len() <= seats_left {
+                // Everyone left fills the remaining seats.
+                round.elected = by_votes.iter().map(|(choice, _)| *choice).collect();
+            } else {
+                round.elected = by_votes
+                    .iter()
+                    .filter(|(_, votes)| *votes >= ratio(quota))
+                    .map(|(choice, _)| *choice)
+                    .take(seats_left)
+                    .collect();
+            }
+
+            let mut round_exhausted = ratio(0);
+            let mut absorb = |received: BTreeMap<BallotChoice, BigRational>,
+                              lost: BigRational,
+                              round: &mut RoundBreakdown| {
+                for (choice, votes) in received {
+                    *round.transfers.entry(choice).or_insert_with(|| ratio(0)) += votes;
+                }
+                round_exhausted += lost;
+            };
+
+            if round.elected.is_empty() {
+                let eliminated = pick_eliminate(&votes_by_candidate, &rounds);
+                continuing.remove(&eliminated);
+                let parcels = piles.remove(&eliminated).unwrap_or_default();
+                let (received, lost) =
+                    transfer(parcels, &ratio(1), &ballots, &continuing, &mut piles);
+                absorb(received, lost, &mut round);
+                round.eliminated = Some(eliminated);
+            } else {
+                for choice in round.elected.iter() {
+                    continuing.remove(choice);
+                }
+                // Gregory method: each elected candidate's surplus moves on at
+                // surplus / votes of every parcel's value, largest first.
+                for choice in round.elected.clone() {
+                    let votes = &votes_by_candidate[&choice];
+                    let parcels = piles.remove(&choice).unwrap_or_default();
+                    if *votes <= ratio(quota) {
+                        continue;
+                    }
+                    let transfer_value = (votes - ratio(quota)) / votes;
+                    let (received, lost) =
+                        transfer(parcels, &transfer_value, &ballots, &continuing, &mut piles);
+                    absorb(received, lost, &mut round);
+                }
+                elected.extend(round.elected.iter());
+            }
+
+            exhausted = round.exhausted.clone() + &round_exhausted;
+            round.exhausted = exhausted.clone();
+            rounds.push(round);
+        }
+
+        return Ok(VoteBreakdown {
+            seats,
+            quota,
+            elected,
+            rounds,
+        });
+    }
+
+    // Formats an exact vote count, with a decimal approximation for fractions.
+    fn votes_str(votes: &BigRational) -> String {
+        if votes.is_integer() {
+            return votes.to_string();
+        }
+        let hundredths = (votes * ratio(100)).round().to_integer();
+        return format!(
+            "{votes} (~{}.{:0>2})",
+            &hundredths / 100u32,
+            &hundredths % 100u32
+        );
+    }
+
+    /// Single transferable vote filling `seats` seats from ranked ballots.
+    pub struct SingleTransferableVote {
+        pub seats: u32,
+    }
+
+    impl vm::VotingMethod for SingleTransferableVote {
+        type Ballot = cv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = VoteError;
+
+        fn name(&self) -> &'static str {
+            "Single transferable vote"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<cv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, VoteError> {
+            let breakdown = vote(num_choices, self.seats, ballots)?;
+
+            // Elected candidates in order of election, then everyone still
+            // standing by their last votes, then everyone eliminated in
+            // reverse order of elimination.
+            let mut ranking: Vec<Vec<vm::OptionId>> = breakdown
+                .elected
+                .iter()
+                .map(|choice| vec![*choice])
+                .collect();
+            if let Some(last_round) = breakdown.rounds.last() {
+                ranking.extend(vm::ranking_by_score(
+                    last_round
+                        .votes_by_candidate
+                        .iter()
+                        .filter(|(choice, _)| !breakdown.elected.contains(choice))
+                        .map(|(choice, votes)| (*choice, votes.clone())),
+                ));
+            }
+            for round in breakdown.rounds.iter().rev() {
+                if let Some(eliminated) = round.eliminated {
+                    ranking.push(vec![eliminated]);
+                }
+            }
+
+            return Ok(vm::Outcome {
+                winners: breakdown.elected.clone(),
+                ranking,
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let elected: Vec<String> = result
+                .elected
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = format!(
+                "Elected via single transferable vote ({} seats, quota {}): {}.\n",
+                result.seats,
+                result.quota,
+                elected.join(", "),
+            );
+
+            for (i, round) in result.rounds.iter().enumerate() {
+                let counts: Vec<String> = round
+                    .votes_by_candidate
+                    .iter()
+                    .map(|(choice, votes)| {
+                        format!(
+                            "{} {}",
+                            vm::option_label(*choice, option_id_to_option),
+                            votes_str(votes)
+                        )
+                    })
+                    .collect();
+                message += &format!("Round {}: {}.", i + 1, counts.join(", "));
+
+                if !round.elected.is_empty() {
+                    let elected: Vec<String> = round
+                        .elected
+                        .iter()
+                        .map(|choice| vm::option_label(*choice, option_id_to_option))
+                        .collect();
+                    message += &format!(" Elected {}", elected.join(", "));
+                }
+                if let Some(eliminated) = round.eliminated {
+                    message += &format!(
+                        " Eliminated {}",
+                        vm::option_label(eliminated, option_id_to_option)
+                    );
+                }
+                let transfers: Vec<String> = round
+                    .transfers
+                    .iter()
+                    .map(|(choice, votes)| {
+                        format!(
+                            "+{} to {}",
+                            votes_str(votes),
+                            vm::option_label(*choice, option_id_to_option)
+                        )
+                    })
+                    .collect();
+                if !transfers.is_empty() {
+                    message += &format!("; {}", transfers.join(", "));
+                }
+                message += &format!(". Exhausted so far: {}\n", votes_str(&round.exhausted));
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            vec![vec![0], vec![1]],
+            vec![vec![0], vec![1]],
+            vec![vec![0], vec![2]],
+            vec![vec![2]],
+            vec![vec![1]],
+        ];
+        let method = SingleTransferableVote { seats: 2 };
+        let outcome = method.tally(3, ballots).unwrap();
+        // Quota 2. 0 is elected with a surplus of 1 split 2/3 to 1, 1/3 to 2.
+        assert_eq!(outcome.winners, vec![0, 1]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2]]);
+
+        let options = BTreeMap::from([
+            (0, "Dune".to_string()),
+            (1, "Emma".to_string()),
+            (2, "Moby".to_string()),
+        ]);
+        assert!(
+            method
+                .describe(&outcome, &options)
+                .contains("+2/3 (~0.67) to 1 (Emma)")
+        );
+    }
+}
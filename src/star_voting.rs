@@ -0,0 +1,275 @@
+pub mod star {
+    use crate::score_voting::sv;
+    use crate::voting_method::vm;
+    use std::collections::BTreeMap;
+
+    pub type BallotChoice = u32;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct VoteBreakdown {
+        /// The runoff winner, or both finalists if the runoff is a complete tie.
+        pub winners: Vec<BallotChoice>,
+        pub totals: BTreeMap<BallotChoice, u64>,
+        /// The two options with the highest total scores.
+        pub finalists: Vec<BallotChoice>,
+        /// Ballots scoring each finalist above the other.
+        pub runoff: BTreeMap<BallotChoice, u64>,
+        /// Ballots scoring both finalists the same.
+        pub no_preference: u64,
+        pub num_ballots: u64,
+    }
+
+    #[test]
+    fn test_finalists() {
+        let totals = BTreeMap::from([(0, 10), (1, 12), (2, 10), (3, 4)]);
+        let ballots = vec![
+            sv::Ballot::from([(0, 5), (1, 4)]),
+            sv::Ballot::from([(1, 4), (2, 4)]),
+        ];
+        // 0 and 2 tie on total; 0 has more top scores.
+        assert_eq!(finalists(&totals, &ballots), vec![1, 0]);
+
+        let ballots = vec![sv::Ballot::from([(0, 4)]), sv::Ballot::from([(2, 4)])];
+        // Still tied, so the lower option ID goes through.
+        assert_eq!(finalists(&totals, &ballots), vec![1, 0]);
+
+        assert_eq!(finalists(&BTreeMap::from([(0, 3)]), &ballots), vec![0]);
+    }
+
+    // The two options with the highest totals. Ties go to the option with
+    // more top scores, then to the lower option ID.
+    fn finalists(
+        totals: &BTreeMap<BallotChoice, u64>,
+        ballots: &[sv::Ballot],
+    ) -> Vec<BallotChoice> {
+        let top_scores = |choice: &BallotChoice| {
+            ballots
+                .iter()
+                .filter(|ballot| ballot.get(choice) == Some(&sv::MAX_SCORE))
+                .count()
+        };
+
+        let mut by_total: Vec<(BallotChoice, u64)> = totals
+            .iter()
+            .map(|(choice, total)| (*choice, *total))
+            .collect();
+        by_total.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then(top_scores(&b.0).cmp(&top_scores(&a.0)))
+                .then(a.0.cmp(&b.0))
+        });
+        return by_total
+            .into_iter()
+            .take(2)
+            .map(|(choice, _)| choice)
+            .collect();
+    }
+
+    #[test]
+    fn test_vote() {
+        struct Case {
+            num_choices: u32,
+            ballots: Vec<sv::Ballot>,
+            expected_finalists: Vec<BallotChoice>,
+            expected_winners: Vec<BallotChoice>,
+        }
+
+        let cases = [
+            // 1 has the highest total but more voters prefer 0 in the runoff.
+            Case {
+                num_choices: 3,
+                ballots: vec![
+                    sv::Ballot::from([(0, 5), (1, 4)]),
+                    sv::Ballot::from([(0, 3), (1, 2)]),
+                    sv::Ballot::from([(0, 0), (1, 5), (2, 1)]),
+                ],
+                expected_finalists: vec![1, 0],
+                expected_winners: vec![0],
+            },
+            // A tied runoff goes to the higher total.
+            Case {
+                num_choices: 2,
+                ballots: vec![
+                    sv::Ballot::from([(0, 5), (1, 0)]),
+                    sv::Ballot::from([(0, 3), (1, 4)]),
+                ],
+                expected_finalists: vec![0, 1],
+                expected_winners: vec![0],
+            },
+            // A complete tie.
+            Case {
+                num_choices: 2,
+                ballots: vec![sv::Ballot::from([(0, 5)]), sv::Ballot::from([(1, 5)])],
+                expected_finalists: vec![0, 1],
+                expected_winners: vec![0, 1],
+            },
+        ];
+
+        for case in cases.iter() {
+            let result = vote(case.num_choices, case.ballots.clone()).unwrap();
+            assert_eq!(result.finalists, case.expected_finalists);
+            assert_eq!(result.winners, case.expected_winners);
+        }
+
+        assert!(vote(2, vec![]).is_err());
+        assert!(vote(2, vec![sv::Ballot::from([(0, 9)])]).is_err());
+    }
+
+    pub fn vote(
+        num_choices: u32,
+        ballots: Vec<sv::Ballot>,
+    ) -> Result<VoteBreakdown, sv::VoteError> {
+        sv::validate(num_choices, &ballots)?;
+
+        let totals = sv::totals(num_choices, &ballots);
+        let finalists = finalists(&totals, &ballots);
+        let mut runoff: BTreeMap<BallotChoice, u64> =
+            finalists.iter().map(|choice| (*choice, 0)).collect();
+        let mut no_preference = 0;
+
+        let winners = match finalists.as_slice() {
+            [first, second] => {
+                for ballot in ballots.iter() {
+                    let score = |choice| ballot.get(choice).copied().unwrap_or(0);
+                    match score(first).cmp(&score(second)) {
+                        std::cmp::Ordering::Greater => *runoff.entry(*first).or_default() += 1,
+                        std::cmp::Ordering::Less => *runoff.entry(*second).or_default() += 1,
+                        std::cmp::Ordering::Equal => no_preference += 1,
+                    }
+                }
+
+                let by_runoff = runoff[first].cmp(&runoff[second]);
+                match by_runoff.then(totals[first].cmp(&totals[second])) {
+                    std::cmp::Ordering::Greater => vec![*first],
+                    std::cmp::Ordering::Less => vec![*second],
+                    std::cmp::Ordering::Equal => vec![*first, *second],
+                }
+            }
+            _ => finalists.clone(),
+        };
+
+        return Ok(VoteBreakdown {
+            winners,
+            totals,
+            finalists,
+            runoff,
+            no_preference,
+            num_ballots: vm::count(ballots.len()),
+        });
+    }
+
+    /// STAR voting: score then automatic runoff between the top two totals.
+    pub struct StarVoting;
+
+    impl vm::VotingMethod for StarVoting {
+        type Ballot = sv::Ballot;
+        type Details = VoteBreakdown;
+        type Error = sv::VoteError;
+
+        fn name(&self) -> &'static str {
+            "STAR"
+        }
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<sv::Ballot>,
+        ) -> Result<vm::Outcome<VoteBreakdown>, sv::VoteError> {
+            let breakdown = vote(num_choices, ballots)?;
+
+            // The runoff decides the top two places, totals the rest.
+            let mut ranking: Vec<Vec<vm::OptionId>> = vec![breakdown.winners.clone()];
+            let runner_up: Vec<vm::OptionId> = breakdown
+                .finalists
+                .iter()
+                .filter(|choice| !breakdown.winners.contains(choice))
+                .copied()
+                .collect();
+            if !runner_up.is_empty() {
+                ranking.push(runner_up);
+            }
+            ranking.extend(vm::ranking_by_score(
+                breakdown
+                    .totals
+                    .iter()
+                    .filter(|(choice, _)| !breakdown.finalists.contains(choice))
+                    .map(|(choice, total)| (*choice, *total)),
+            ));
+
+            return Ok(vm::Outcome {
+                winners: breakdown.winners.clone(),
+                ranking,
+                details: breakdown,
+            });
+        }
+
+        fn describe(
+            &self,
+            outcome: &vm::Outcome<VoteBreakdown>,
+            option_id_to_option: &BTreeMap<vm::OptionId, String>,
+        ) -> String {
+            let result = &outcome.details;
+            let winners: Vec<String> = result
+                .winners
+                .iter()
+                .map(|choice| format!("**{}**", vm::option_name(*choice, option_id_to_option)))
+                .collect();
+            let mut message = match winners.len() {
+                1 => format!("STAR winner: {}.", winners[0]),
+                _ => format!("Tied STAR winners: {}.", winners.join(", ")),
+            };
+
+            let totals: Vec<String> = result
+                .totals
+                .iter()
+                .map(|(choice, total)| {
+                    format!(
+                        "{} {}",
+                        vm::option_label(*choice, option_id_to_option),
+                        sv::total_str(*total, result.num_ballots)
+                    )
+                })
+                .collect();
+            message += &format!("\nScoring round: {}", totals.join(", "));
+
+            if result.finalists.len() == 2 {
+                let runoff: Vec<String> = result
+                    .finalists
+                    .iter()
+                    .map(|choice| {
+                        format!(
+                            "{} preferred on {}",
+                            vm::option_label(*choice, option_id_to_option),
+                            result.runoff[choice]
+                        )
+                    })
+                    .collect();
+                message += &format!(
+                    "\nAutomatic runoff: {}, no preference on {}",
+                    runoff.join(", "),
+                    result.no_preference
+                );
+            }
+            return message;
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        use crate::voting_method::vm::VotingMethod;
+
+        let ballots = vec![
+            sv::Ballot::from([(0, 5), (1, 4)]),
+            sv::Ballot::from([(0, 3), (1, 2)]),
+            sv::Ballot::from([(1, 5), (2, 1)]),
+        ];
+        let outcome = StarVoting.tally(3, ballots).unwrap();
+        assert_eq!(outcome.winners, vec![0]);
+        assert_eq!(outcome.ranking, vec![vec![0], vec![1], vec![2]]);
+
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert!(StarVoting.describe(&outcome, &options).ends_with(
+            "Automatic runoff: 1 (Emma) preferred on 1, 0 (Dune) preferred on 2, no preference on 0"
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+pub mod vm {
+    use std::collections::BTreeMap;
+    use std::error::Error;
+    use std::fmt;
+
+    pub type OptionId = u32;
+
+    // Tallies index vectors and matrices by option and count ballots in u64,
+    // so `usize` must hold every `OptionId` and fit in a `u64`. That makes the
+    // conversions below lossless.
+    const _: () = assert!(usize::BITS >= OptionId::BITS && usize::BITS <= u64::BITS);
+
+    /// The vector or matrix index of an option.
+    pub fn index(choice: OptionId) -> usize {
+        return choice as usize;
+    }
+
+    /// The option at a vector or matrix index. Indexes come from collections
+    /// sized by a `num_choices: OptionId`, so they always fit.
+    pub fn option_id(index: usize) -> OptionId {
+        return index as OptionId;
+    }
+
+    /// A length or number of ballots as a count.
+    pub fn count(n: usize) -> u64 {
+        return n as u64;
+    }
+
+    /// The result of a tally in a shape shared by every voting method.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Outcome<D> {
+        /// Every option sharing first place. Empty if there is no winner.
+        pub winners: Vec<OptionId>,
+        /// All options from best to worst. Options in the same group are tied.
+        pub ranking: Vec<Vec<OptionId>>,
+        /// Method-specific breakdown of how the result was reached.
+        pub details: D,
+    }
+
+    /// A way of turning ballots into an `Outcome`. Implementations carry their
+    /// session configuration (token budgets, seat counts, ...) in `self`.
+    pub trait VotingMethod {
+        type Ballot: Clone + fmt::Debug;
+        type Details: Clone + fmt::Debug;
+        type Error: Error;
+
+        fn name(&self) -> &'static str;
+
+        fn tally(
+            &self,
+            num_choices: u32,
+            ballots: Vec<Self::Ballot>,
+        ) -> Result<Outcome<Self::Details>, Self::Error>;
+
+        /// Method-specific explanation of `outcome` for the results post.
+        fn describe(
+            &self,
+            outcome: &Outcome<Self::Details>,
+            option_id_to_option: &BTreeMap<OptionId, String>,
+        ) -> String;
+    }
+
+    #[test]
+    fn test_ranking_by_score() {
+        struct Case {
+            scores: Vec<(OptionId, i64)>,
+            expected: Vec<Vec<OptionId>>,
+        }
+
+        let cases = [
+            Case {
+                scores: vec![(0, 3), (1, 5), (2, -1)],
+                expected: vec![vec![1], vec![0], vec![2]],
+            },
+            Case {
+                scores: vec![(0, 3), (1, 5), (2, 3), (3, 0)],
+                expected: vec![vec![1], vec![0, 2], vec![3]],
+            },
+            Case {
+                scores: vec![],
+                expected: vec![],
+            },
+        ];
+
+        for case in cases.iter() {
+            assert_eq!(ranking_by_score(case.scores.clone()), case.expected);
+        }
+    }
+
+    /// Ranks options by descending score, grouping equal scores together.
+    pub fn ranking_by_score<S: Ord>(
+        scores: impl IntoIterator<Item = (OptionId, S)>,
+    ) -> Vec<Vec<OptionId>> {
+        let mut scores: Vec<(OptionId, S)> = scores.into_iter().collect();
+        scores.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut ranking: Vec<Vec<OptionId>> = Vec::new();
+        let mut previous: Option<&S> = None;
+        for (choice, score) in scores.iter() {
+            match (previous, ranking.last_mut()) {
+                (Some(previous_score), Some(group)) if previous_score == score => {
+                    group.push(*choice);
+                }
+                (_, _) => ranking.push(vec![*choice]),
+            }
+            previous = Some(score);
+        }
+        return ranking;
+    }
+
+    #[test]
+    fn test_ranking_str() {
+        let options = BTreeMap::from([(0, "Dune".to_string()), (1, "Emma".to_string())]);
+        assert_eq!(
+            ranking_str(&[vec![1], vec![0, 2]], &options),
+            "Emma > Dune = 2"
+        );
+        assert_eq!(ranking_str(&[], &options), "");
+    }
+
+    /// Formats a ranking as e.g. "Dune > Emma = Moby", using option names
+    /// where they are known.
+    pub fn ranking_str(
+        ranking: &[Vec<OptionId>],
+        option_id_to_option: &BTreeMap<OptionId, String>,
+    ) -> String {
+        ranking
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|choice| option_name(*choice, option_id_to_option))
+                    .collect::<Vec<String>>()
+                    .join(" = ")
+            })
+            .collect::<Vec<String>>()
+            .join(" > ")
+    }
+
+    /// The name of an option, or its ID if the name is unknown.
+    pub fn option_name(
+        option_id: OptionId,
+        option_id_to_option: &BTreeMap<OptionId, String>,
+    ) -> String {
+        match option_id_to_option.get(&option_id) {
+            Some(option) => option.clone(),
+            None => option_id.to_string(),
+        }
+    }
+
+    /// Formats an option as "ID (name)" for round-by-round breakdowns.
+    pub fn option_label(
+        option_id: OptionId,
+        option_id_to_option: &BTreeMap<OptionId, String>,
+    ) -> String {
+        match option_id_to_option.get(&option_id) {
+            Some(option) => format!("{option_id} ({option})"),
+            None => format!("{option_id}"),
+        }
+    }
+
+    #[test]
+    fn test_seeded_rng() {
+        // Reference values for SplitMix64 seeded with 0
+        let mut rng = SeededRng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+        assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+
+        let mut a: Vec<u32> = (0..10).collect();
+        let mut b: Vec<u32> = (0..10).collect();
+        SeededRng::new(42).shuffle(&mut a);
+        SeededRng::new(42).shuffle(&mut b);
+        assert_eq!(a, b);
+        b.sort();
+        assert_eq!(b, (0..10).collect::<Vec<u32>>());
+
+        // Equal weights shuffle the same way as no weights.
+        let mut c: Vec<u32> = (0..10).collect();
+        SeededRng::new(42).weighted_shuffle(&mut c, |_| 3);
+        assert_eq!(c, a);
+
+        // An item with nearly all the weight almost always comes first, and
+        // one with none comes last.
+        for seed in 0..20 {
+            let mut items: Vec<u64> = vec![1, 1000, 1, 0];
+            SeededRng::new(seed).weighted_shuffle(&mut items, |weight| *weight);
+            assert_eq!(items[0], 1000);
+            assert_eq!(items[3], 0);
+        }
+    }
+
+    /// SplitMix64 (https://prng.di.unimi.it/splitmix64.c). It is tiny and
+    /// fully specified, so anyone can reproduce a tie-break or resampling
+    /// from its published seed.
+    pub struct SeededRng {
+        state: u64,
+    }
+
+    impl SeededRng {
+        pub fn new(seed: u64) -> SeededRng {
+            SeededRng { state: seed }
+        }
+
+        pub fn next_u64(&mut self) -> u64 {
+            self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = self.state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            return z ^ (z >> 31);
+        }
+
+        /// A number in `0..n`.
+        pub fn below(&mut self, n: usize) -> usize {
+            let scaled = (u128::from(self.next_u64()) * u128::from(count(n))) >> 64;
+            // Below `n`, so it fits.
+            return scaled as usize;
+        }
+
+        /// Fisher-Yates shuffle.
+        pub fn shuffle<T>(&mut self, items: &mut [T]) {
+            for i in (1..items.len()).rev() {
+                let j = self.below(i + 1);
+                items.swap(i, j);
+            }
+        }
+
+        /// Shuffles `items` so each place, from the front, goes to one of the
+        /// items left with chance proportional to its `weight`. Equal weights
+        /// shuffle exactly as `shuffle` does, so published seeds still
+        /// reproduce.
+        pub fn weighted_shuffle<T>(&mut self, items: &mut [T], weight: impl Fn(&T) -> u64) {
+            if items
+                .windows(2)
+                .all(|pair| weight(&pair[0]) == weight(&pair[1]))
+            {
+                self.shuffle(items);
+                return;
+            }
+            for i in 0..items.len() {
+                let total: u128 = items[i..].iter().map(|item| u128::from(weight(item))).sum();
+                if total == 0 {
+                    self.shuffle(&mut items[i..]);
+                    return;
+                }
+                // Below `total`, which is the sum of the weights left.
+                let mut drawn = (u128::from(self.next_u64()) * total) >> 64;
+                let mut j = i;
+                while drawn >= u128::from(weight(&items[j])) {
+                    drawn -= u128::from(weight(&items[j]));
+                    j += 1;
+                }
+                items.swap(i, j);
+            }
+        }
+    }
+}